@@ -0,0 +1,250 @@
+pub use crate::cycle::Cycle;
+use crate::fill::Connectivity;
+use crate::{Dir4, Dir8, Map, Pos};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// Steps a [`Map`] by applying `rule` to every cell at once.
+/// The rule gets the cell and its neighbors, ordered clockwise
+/// starting north like [`Dir4::ALL`] or [`Dir8::ALL`],
+/// where neighbors outside the map are `None`.
+pub struct Automaton<T, F> {
+    current: Map<T>,
+    next: Map<T>,
+    rule: F,
+    connectivity: Connectivity,
+    steps: usize,
+    /// States from step `history_start` on, only recorded while looking for a cycle.
+    history: Vec<Map<T>>,
+    history_start: usize,
+    seen: HashMap<Map<T>, usize>,
+}
+
+impl<T, F> Automaton<T, F>
+where
+    T: Clone + Eq + Hash,
+    F: Fn(&T, &[Option<&T>]) -> T,
+{
+    pub fn new(map: Map<T>, connectivity: Connectivity, rule: F) -> Automaton<T, F> {
+        Automaton {
+            next: map.clone(),
+            current: map,
+            rule,
+            connectivity,
+            steps: 0,
+            history: Vec::new(),
+            history_start: 0,
+            seen: HashMap::new(),
+        }
+    }
+
+    pub fn map(&self) -> &Map<T> {
+        &self.current
+    }
+
+    /// Number of steps taken so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Advances one step, returns whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let offsets: Vec<Pos> = match self.connectivity {
+            Connectivity::Four => Dir4::ALL.iter().map(Dir4::offset).collect(),
+            Connectivity::Eight => Dir8::ALL.iter().map(Dir8::offset).collect(),
+        };
+
+        let mut changed = false;
+        let mut neighbors = Vec::with_capacity(offsets.len());
+        for i in 0..self.current.content.len() {
+            let pos = Pos::from((
+                (i % self.current.width) as isize,
+                (i / self.current.width) as isize,
+            ));
+            neighbors.clear();
+            neighbors.extend(offsets.iter().map(|o| self.current.get(&(&pos + o))));
+
+            let cell = &self.current.content[i];
+            let new = (self.rule)(cell, &neighbors);
+            changed |= new != *cell;
+            self.next.content[i] = new;
+        }
+
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.steps += 1;
+        changed
+    }
+
+    /// Steps until nothing changes anymore or `max_steps` are taken in total.
+    /// Returns the number of steps taken if a fixpoint was reached.
+    pub fn run_until_stable(&mut self, max_steps: usize) -> Option<usize> {
+        while self.steps < max_steps {
+            if !self.step() {
+                return Some(self.steps);
+            }
+        }
+        None
+    }
+
+    /// Remembers the current state, returns the step it was seen before, if any.
+    fn record(&mut self) -> Option<usize> {
+        // the history must be without gaps up to the current step
+        if self.history_start + self.history.len() != self.steps {
+            self.history.clear();
+            self.seen.clear();
+            self.history_start = self.steps;
+        }
+
+        if let Some(&start) = self.seen.get(&self.current) {
+            return Some(start);
+        }
+        self.seen.insert(self.current.clone(), self.steps);
+        self.history.push(self.current.clone());
+        None
+    }
+
+    /// Steps until a state repeats, comparing hashes of every state seen.
+    /// A fixpoint is a cycle of length one.
+    pub fn find_cycle(&mut self) -> Cycle {
+        loop {
+            if let Some(start) = self.record() {
+                return Cycle {
+                    start,
+                    len: self.steps - start,
+                };
+            }
+            self.step();
+        }
+    }
+
+    /// The state after `n` steps in total, without simulating them all
+    /// if the states repeat before. Stops stepping once a cycle is found.
+    ///
+    /// # Panics
+    /// If more than `n` steps were already taken.
+    pub fn state_at(&mut self, n: usize) -> Map<T> {
+        assert!(n >= self.steps, "Can't step back to {n}");
+
+        loop {
+            if self.steps == n {
+                return self.current.clone();
+            }
+            if let Some(start) = self.record() {
+                let cycle = Cycle {
+                    start,
+                    len: self.steps - start,
+                };
+                return self.history[cycle.reduce(n) - self.history_start].clone();
+            }
+            self.step();
+        }
+    }
+}
+
+impl<T: fmt::Display, F> fmt::Display for Automaton<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        writeln!(f, "Step {}:", self.steps)?;
+        write!(f, "{}", self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn game_of_life(cell: &char, neighbors: &[Option<&char>]) -> char {
+        let alive = neighbors.iter().filter(|n| **n == Some(&'#')).count();
+        match (cell, alive) {
+            ('#', 2 | 3) | ('.', 3) => '#',
+            _ => '.',
+        }
+    }
+
+    const BLINKER: &str = ".....
+..#..
+..#..
+..#..
+.....
+";
+
+    #[test]
+    fn test_step() {
+        let map: Map<char> = Map::from_str(BLINKER).unwrap();
+        let mut life = Automaton::new(map, Connectivity::Eight, game_of_life);
+
+        assert!(life.step());
+        assert_eq!(
+            life.map().to_string(),
+            ".....\n.....\n.###.\n.....\n.....\n"
+        );
+        assert!(life.step());
+        assert_eq!(life.map().to_string(), BLINKER);
+        assert_eq!(life.steps(), 2);
+        assert_eq!(life.to_string(), format!("Step 2:\n{BLINKER}"));
+    }
+
+    #[test]
+    fn test_fixpoint() {
+        let map: Map<char> = Map::from_str(
+            "....
+.##.
+.#..
+....
+",
+        )
+        .unwrap();
+        let mut life = Automaton::new(map, Connectivity::Eight, game_of_life);
+
+        assert_eq!(life.run_until_stable(100), Some(2));
+        assert_eq!(life.map().to_string(), "....\n.##.\n.##.\n....\n");
+
+        let map: Map<char> = Map::from_str(BLINKER).unwrap();
+        let mut blinker = Automaton::new(map, Connectivity::Eight, game_of_life);
+        assert_eq!(blinker.run_until_stable(100), None);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let map: Map<char> = Map::from_str(BLINKER).unwrap();
+        let mut life = Automaton::new(map, Connectivity::Eight, game_of_life);
+        assert_eq!(life.find_cycle(), Cycle { start: 0, len: 2 });
+
+        // a counter in every cell, that cycles through 1..=3 once it hits 3
+        let map = Map {
+            content: vec![0u8, 1, 2],
+            dims: [3, 1],
+        };
+        let mut counter = Automaton::new(
+            map,
+            Connectivity::Four,
+            |c: &u8, _: &[Option<&u8>]| {
+                if *c == 3 {
+                    1
+                } else {
+                    c + 1
+                }
+            },
+        );
+        assert_eq!(counter.find_cycle(), Cycle { start: 1, len: 3 });
+        assert_eq!(counter.state_at(1_000_000_000).content, vec![1, 2, 3]);
+        assert_eq!(counter.state_at(1_000_000_001).content, vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn test_state_at() {
+        let map: Map<char> = Map::from_str(BLINKER).unwrap();
+        let mut life = Automaton::new(map, Connectivity::Eight, game_of_life);
+
+        assert_eq!(
+            life.state_at(1).to_string(),
+            ".....\n.....\n.###.\n.....\n.....\n"
+        );
+        assert_eq!(life.state_at(1_000_000_000).to_string(), BLINKER);
+        assert_eq!(
+            life.state_at(1_000_000_001).to_string(),
+            ".....\n.....\n.###.\n.....\n.....\n"
+        );
+    }
+}
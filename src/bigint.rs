@@ -0,0 +1,646 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Arbitrary precision integer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    /// Magnitude in base 2^32, least significant limb first, without leading zeros.
+    mag: Vec<u32>,
+}
+
+fn trim(mut mag: Vec<u32>) -> Vec<u32> {
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+    mag
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &l) in long.iter().enumerate() {
+        let s = l as u64 + short.get(i).copied().unwrap_or(0) as u64 + carry;
+        sum.push(s as u32);
+        carry = s >> 32;
+    }
+    sum.push(carry as u32);
+    trim(sum)
+}
+
+/// `a - b` for `a >= b`.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut diff = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &x) in a.iter().enumerate() {
+        let mut d = x as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = (d < 0) as i64;
+        d += borrow << 32;
+        diff.push(d as u32);
+    }
+    trim(diff)
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut prod = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let p = x as u64 * y as u64 + prod[i + j] as u64 + carry;
+            prod[i + j] = p as u32;
+            carry = p >> 32;
+        }
+        prod[i + b.len()] = carry as u32;
+    }
+    trim(prod)
+}
+
+/// `a * m + c` for a single limb `m` and `c`.
+fn mul_add_small(a: &[u32], m: u32, c: u32) -> Vec<u32> {
+    let mut prod = Vec::with_capacity(a.len() + 1);
+    let mut carry = c as u64;
+    for &x in a {
+        let p = x as u64 * m as u64 + carry;
+        prod.push(p as u32);
+        carry = p >> 32;
+    }
+    prod.push(carry as u32);
+    trim(prod)
+}
+
+/// Quotient and remainder of a division by a single limb `d > 0`.
+fn divrem_small(a: &[u32], d: u32) -> (Vec<u32>, u32) {
+    let mut quot = vec![0; a.len()];
+    let mut rem = 0u64;
+    for (i, &x) in a.iter().enumerate().rev() {
+        let cur = (rem << 32) | x as u64;
+        quot[i] = (cur / d as u64) as u32;
+        rem = cur % d as u64;
+    }
+    (trim(quot), rem as u32)
+}
+
+/// `a << shift` for `shift < 32`, with one more limb for the bits shifted out.
+fn shl_bits(a: &[u32], shift: u32) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+    for &x in a {
+        let w = (x as u64) << shift;
+        out.push(w as u32 | carry);
+        carry = (w >> 32) as u32;
+    }
+    out.push(carry);
+    out
+}
+
+/// `a >> shift` for `shift < 32`.
+fn shr_bits(a: &[u32], shift: u32) -> Vec<u32> {
+    (0..a.len())
+        .map(|i| {
+            let w = (a.get(i + 1).copied().unwrap_or(0) as u64) << 32 | a[i] as u64;
+            (w >> shift) as u32
+        })
+        .collect()
+}
+
+/// Quotient and remainder for `b` not zero, by long division one limb at a time
+/// (Knuth, The Art of Computer Programming Vol. 2, 4.3.1, Algorithm D).
+fn divrem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if b.len() == 1 {
+        let (q, r) = divrem_small(a, b[0]);
+        return (q, trim(vec![r]));
+    }
+    if cmp_mag(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+
+    // with the top bit of the divisor set, a quotient limb estimated
+    // from the top limbs is at most two too large
+    let shift = b[b.len() - 1].leading_zeros();
+    let n = b.len();
+    let v = &shl_bits(b, shift)[..n];
+    let mut u = shl_bits(a, shift);
+    let (v1, v2) = (v[n - 1] as u64, v[n - 2] as u64);
+
+    let mut quot = vec![0u32; a.len() - n + 1];
+    for j in (0..quot.len()).rev() {
+        let top = (u[j + n] as u64) << 32 | u[j + n - 1] as u64;
+        let (mut qhat, mut rhat) = (top / v1, top % v1);
+        while qhat > u32::MAX as u64 || qhat * v2 > (rhat << 32 | u[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += v1;
+            if rhat > u32::MAX as u64 {
+                break;
+            }
+        }
+
+        // u[j..=j + n] -= qhat * v, in place
+        let (mut carry, mut borrow) = (0u64, 0i64);
+        for i in 0..n {
+            let p = qhat * v[i] as u64 + carry;
+            carry = p >> 32;
+            let t = u[i + j] as i64 - borrow - (p as u32) as i64;
+            u[i + j] = t as u32;
+            borrow = (t < 0) as i64;
+        }
+        let t = u[j + n] as i64 - borrow - carry as i64;
+        u[j + n] = t as u32;
+
+        // rarely the estimate is still one too large, then add one divisor back
+        if t < 0 {
+            qhat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = u[i + j] as u64 + v[i] as u64 + carry;
+                u[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            u[j + n] = u[j + n].wrapping_add(carry as u32);
+        }
+        quot[j] = qhat as u32;
+    }
+    (trim(quot), trim(shr_bits(&u[..n], shift)))
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, mag: Vec<u32>) -> BigInt {
+        let mag = trim(mag);
+        BigInt {
+            negative: negative && !mag.is_empty(),
+            mag,
+        }
+    }
+
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn one() -> BigInt {
+        BigInt::from(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// -1, 0 or 1.
+    pub fn signum(&self) -> i32 {
+        match (self.negative, self.is_zero()) {
+            (true, _) => -1,
+            (false, true) => 0,
+            (false, false) => 1,
+        }
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from_parts(false, self.mag.clone())
+    }
+
+    pub fn pow(&self, mut exp: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// Greatest common divisor, never negative.
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        let (mut a, mut b) = (self.abs(), other.abs());
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    /// Quotient rounded towards negative infinity.
+    pub fn div_floor(&self, other: &BigInt) -> BigInt {
+        let (q, r) = self.div_rem(other);
+        if !r.is_zero() && r.negative != other.negative {
+            q - BigInt::one()
+        } else {
+            q
+        }
+    }
+
+    /// Quotient rounded towards zero and the remainder with the sign of `self`, like `/` and `%`.
+    ///
+    /// # Panics
+    /// If `other` is zero.
+    pub fn div_rem(&self, other: &BigInt) -> (BigInt, BigInt) {
+        assert!(!other.is_zero(), "Division of {self} by zero");
+        let (q, r) = divrem_mag(&self.mag, &other.mag);
+        (
+            BigInt::from_parts(self.negative != other.negative, q),
+            BigInt::from_parts(self.negative, r),
+        )
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(n: u128) -> BigInt {
+        let mag = (0..4).map(|i| (n >> (32 * i)) as u32).collect();
+        BigInt::from_parts(false, mag)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(n: i128) -> BigInt {
+        let mut big = BigInt::from(n.unsigned_abs());
+        big.negative = n < 0;
+        big
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(n: u64) -> BigInt {
+        BigInt::from(n as u128)
+    }
+}
+
+impl From<usize> for BigInt {
+    fn from(n: usize) -> BigInt {
+        BigInt::from(n as u128)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> BigInt {
+        BigInt::from(n as i128)
+    }
+}
+
+impl From<isize> for BigInt {
+    fn from(n: isize) -> BigInt {
+        BigInt::from(n as i128)
+    }
+}
+
+impl From<i32> for BigInt {
+    fn from(n: i32) -> BigInt {
+        BigInt::from(n as i128)
+    }
+}
+
+impl TryFrom<&BigInt> for i128 {
+    type Error = Box<dyn Error + 'static>;
+
+    fn try_from(n: &BigInt) -> Result<i128, Self::Error> {
+        if n.mag.len() > 4 {
+            return Err(format!("{n} does not fit into i128").into());
+        }
+        let mag = n
+            .mag
+            .iter()
+            .enumerate()
+            .fold(0u128, |acc, (i, &l)| acc | (l as u128) << (32 * i));
+        let value = if n.negative {
+            0i128.checked_sub_unsigned(mag)
+        } else {
+            i128::try_from(mag).ok()
+        };
+        value.ok_or_else(|| format!("{n} does not fit into i128").into())
+    }
+}
+
+impl TryFrom<&BigInt> for i64 {
+    type Error = Box<dyn Error + 'static>;
+
+    fn try_from(n: &BigInt) -> Result<i64, Self::Error> {
+        Ok(i64::try_from(i128::try_from(n)?)?)
+    }
+}
+
+impl TryFrom<&BigInt> for u64 {
+    type Error = Box<dyn Error + 'static>;
+
+    fn try_from(n: &BigInt) -> Result<u64, Self::Error> {
+        Ok(u64::try_from(i128::try_from(n)?)?)
+    }
+}
+
+impl TryFrom<&BigInt> for usize {
+    type Error = Box<dyn Error + 'static>;
+
+    fn try_from(n: &BigInt) -> Result<usize, Self::Error> {
+        Ok(usize::try_from(i128::try_from(n)?)?)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.mag, &other.mag),
+            (true, true) => cmp_mag(&other.mag, &self.mag),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.mag.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.mag)
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, add_mag(&self.mag, &rhs.mag));
+        }
+        match cmp_mag(&self.mag, &rhs.mag) {
+            Ordering::Less => BigInt::from_parts(rhs.negative, sub_mag(&rhs.mag, &self.mag)),
+            _ => BigInt::from_parts(self.negative, sub_mag(&self.mag, &rhs.mag)),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != rhs.negative, mul_mag(&self.mag, &rhs.mag))
+    }
+}
+
+/// Rounds towards zero like the primitive integers.
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+/// Has the sign of the dividend like the primitive integers.
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).1
+    }
+}
+
+/// Implements an operator for owned operands by borrowing them.
+macro_rules! forward_owned {
+    ($($op:ident $method:ident),*) => {$(
+        impl $op for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, rhs: BigInt) -> BigInt {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $op<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, rhs: &BigInt) -> BigInt {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $op<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, rhs: BigInt) -> BigInt {
+                self.$method(&rhs)
+            }
+        }
+    )*};
+}
+
+forward_owned!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl AddAssign<&BigInt> for BigInt {
+    fn add_assign(&mut self, rhs: &BigInt) {
+        *self = &*self + rhs;
+    }
+}
+
+impl SubAssign<&BigInt> for BigInt {
+    fn sub_assign(&mut self, rhs: &BigInt) {
+        *self = &*self - rhs;
+    }
+}
+
+impl MulAssign<&BigInt> for BigInt {
+    fn mul_assign(&mut self, rhs: &BigInt) {
+        *self = &*self * rhs;
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = Box<dyn Error + 'static>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() {
+            return Err(format!("No digits in {s:?}").into());
+        }
+
+        let mut mag = Vec::new();
+        for c in digits.chars() {
+            let d = c
+                .to_digit(10)
+                .ok_or_else(|| format!("Invalid digit {c:?} in {s:?}"))?;
+            mag = mul_add_small(&mag, 10, d);
+        }
+        Ok(BigInt::from_parts(negative, mag))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        // split into chunks of 9 decimal digits, least significant first
+        let mut chunks = Vec::new();
+        let mut mag = self.mag.clone();
+        while !mag.is_empty() {
+            let (q, r) = divrem_small(&mag, 1_000_000_000);
+            chunks.push(r);
+            mag = q;
+        }
+
+        let mut digits = chunks.pop().unwrap_or(0).to_string();
+        for c in chunks.iter().rev() {
+            digits.push_str(&format!("{c:09}"));
+        }
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lcg;
+
+    /// Numbers of all sizes up to `i64`.
+    fn random_i64(rng: &mut Lcg) -> i64 {
+        let state = rng.next_u64();
+        (state as i64) >> (state % 60)
+    }
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        for s in [
+            "0",
+            "7",
+            "-7",
+            "4294967296",
+            "-123456789012345678901234567890",
+        ] {
+            assert_eq!(big(s).to_string(), s);
+        }
+        assert_eq!(big("-0"), BigInt::zero());
+        assert_eq!(big("+0042").to_string(), "42");
+        assert_eq!(format!("{:>5}", big("-42")), "  -42");
+        assert!("".parse::<BigInt>().is_err());
+        assert!("-".parse::<BigInt>().is_err());
+        assert!("12a".parse::<BigInt>().is_err());
+    }
+
+    #[test]
+    fn test_against_i128() {
+        let mut rng = Lcg(2023);
+        for _ in 0..5000 {
+            let (a, b) = (random_i64(&mut rng) as i128, random_i64(&mut rng) as i128);
+            let (ba, bb) = (BigInt::from(a), BigInt::from(b));
+
+            assert_eq!(i128::try_from(&(&ba + &bb)).unwrap(), a + b);
+            assert_eq!(i128::try_from(&(&ba - &bb)).unwrap(), a - b);
+            assert_eq!(i128::try_from(&(&ba * &bb)).unwrap(), a * b);
+            if b != 0 {
+                assert_eq!(i128::try_from(&(&ba / &bb)).unwrap(), a / b);
+                assert_eq!(i128::try_from(&(&ba % &bb)).unwrap(), a % b);
+                let floor = a / b - (a % b != 0 && (a < 0) != (b < 0)) as i128;
+                assert_eq!(i128::try_from(&ba.div_floor(&bb)).unwrap(), floor);
+            }
+            assert_eq!(ba.cmp(&bb), a.cmp(&b));
+            assert_eq!(ba.to_string(), a.to_string());
+        }
+    }
+
+    /// Magnitudes of many limbs, biased to the extreme limbs that need corrections.
+    fn random_big(rng: &mut Lcg) -> BigInt {
+        let limbs = (0..rng.next(8) + 1)
+            .map(|_| match rng.next(4) {
+                0 => 0,
+                1 => u32::MAX,
+                2 => 1 << 31,
+                _ => rng.next_u64() as u32,
+            })
+            .collect();
+        BigInt::from_parts(rng.next(2) == 0, limbs)
+    }
+
+    #[test]
+    fn test_division() {
+        let mut rng = Lcg(42);
+        for _ in 0..5000 {
+            let (a, b) = (random_big(&mut rng), random_big(&mut rng));
+            if b.is_zero() {
+                continue;
+            }
+            let (q, r) = a.div_rem(&b);
+            assert_eq!(&(&q * &b) + &r, a, "{a} / {b}");
+            assert!(r.abs() < b.abs(), "{a} % {b}");
+            assert!(r.is_zero() || r.is_negative() == a.is_negative());
+        }
+
+        // needs the add back step
+        let max = u32::MAX;
+        let a = BigInt::from_parts(false, vec![max, 1052695255, max, max, max]);
+        let b = BigInt::from_parts(false, vec![max, 1 << 31, 1 << 31]);
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(&(&q * &b) + &r, a);
+        assert!(r < b);
+    }
+
+    #[test]
+    fn test_big_values() {
+        let f = (1..=30).fold(BigInt::one(), |acc, n| acc * BigInt::from(n));
+        assert_eq!(f.to_string(), "265252859812191058636308480000000");
+        assert_eq!(
+            &f / &(1..=28).fold(BigInt::one(), |acc, n| acc * BigInt::from(n)),
+            BigInt::from(870)
+        );
+
+        let two_128 = BigInt::from(2).pow(128);
+        assert_eq!(
+            two_128.to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(&two_128 - BigInt::one(), BigInt::from(u128::MAX));
+        assert!(i128::try_from(&two_128).is_err());
+        assert_eq!(
+            i128::try_from(&-(two_128.clone() / BigInt::from(2))).unwrap(),
+            i128::MIN
+        );
+
+        let a = big("123456789012345678901234567890");
+        let b = big("-9876543210987654321");
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(&q * &b + &r, a);
+        assert!(r.abs() < b.abs());
+        assert_eq!(big("60").gcd(&big("-84")), BigInt::from(12));
+        assert_eq!((&a * &b).gcd(&(&a * big("7"))), a);
+    }
+}
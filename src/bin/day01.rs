@@ -1,41 +1,42 @@
 use rustvent2023::get_input;
+use rustvent2023::scanner::Scanner;
 use std::time;
 
-const NUM_2_DIG: [(&str, &str); 9] = [
-    ("one", "1"),
-    ("two", "2"),
-    ("three", "3"),
-    ("four", "4"),
-    ("five", "5"),
-    ("six", "6"),
-    ("seven", "7"),
-    ("eight", "8"),
-    ("nine", "9"),
+const ENGLISH: [(&str, usize); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
 ];
 
-fn part_one(input: &str) -> usize {
-    input
-        .lines()
-        .map(|m: &str| {
-            let mut cs = m.chars().filter(|c| '0' <= *c && *c <= '9');
-            let first = cs.next().unwrap();
-            let last = cs.last().unwrap_or(first);
-            let s = format!("{first}{last}");
-            s.parse::<usize>().unwrap()
-        })
-    .sum()
+/// Scanner for the digits and the given number words.
+fn number_scanner(words: &[(&str, usize)]) -> Scanner<usize> {
+    let digits = (0..10).map(|d| (d.to_string(), d));
+    Scanner::new(digits.chain(words.iter().map(|&(w, n)| (w.to_string(), n))))
 }
 
-fn part_two(input: &str) -> usize {
-    let mut replaced = input.to_string();
+/// The first and last number of the line written next to each other.
+fn calibration(scanner: &Scanner<usize>, line: &str) -> usize {
+    let first = scanner.first(line).unwrap().value;
+    let last = scanner.last(line).unwrap().value;
+    format!("{first}{last}").parse().unwrap()
+}
 
+fn calibration_sum(scanner: &Scanner<usize>, input: &str) -> usize {
+    input.lines().map(|l| calibration(scanner, l)).sum()
+}
 
-    // this can't be any more ugly
-    for (word, dig) in NUM_2_DIG {
-        replaced = replaced.replace(word, &format!("{word}{dig}{word}"));
-    }
+fn part_one(input: &str) -> usize {
+    calibration_sum(&number_scanner(&[]), input)
+}
 
-    part_one(&replaced)
+fn part_two(input: &str) -> usize {
+    calibration_sum(&number_scanner(&ENGLISH), input)
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
@@ -84,4 +85,36 @@ zoneight234
     fn test_part_two() {
         assert_eq!(part_two(TEST2), 281);
     }
+
+    #[test]
+    fn test_overlaps() {
+        let scanner = number_scanner(&ENGLISH);
+        assert_eq!(calibration(&scanner, "eightwo"), 82);
+        assert_eq!(calibration(&scanner, "oneight"), 18);
+        assert_eq!(calibration(&scanner, "twone"), 21);
+        assert_eq!(calibration(&scanner, "sevenine"), 79);
+        assert_eq!(calibration(&scanner, "5"), 55);
+    }
+
+    #[test]
+    fn test_other_vocabularies() {
+        let german = [
+            ("eins", 1),
+            ("zwei", 2),
+            ("drei", 3),
+            ("vier", 4),
+            ("fünf", 5),
+            ("sechs", 6),
+            ("sieben", 7),
+            ("acht", 8),
+            ("neun", 9),
+        ];
+        let scanner = number_scanner(&german);
+        assert_eq!(calibration(&scanner, "xfünfzweiundachtzig"), 58);
+        assert_eq!(calibration(&scanner, "neunsiebeneins"), 91);
+
+        let tens = number_scanner(&[("ten", 10), ("eleven", 11), ("one", 1)]);
+        assert_eq!(calibration(&tens, "tenxone"), 101);
+        assert_eq!(calibration(&tens, "3eleven"), 311);
+    }
 }
@@ -1,65 +1,32 @@
 use lazy_static::lazy_static;
 use regex::Regex;
 use rustvent2023::get_input;
-use std::cmp::max;
+use rustvent2023::multiset::Multiset;
 use std::error::Error;
-use std::fmt;
 use std::str::FromStr;
 use std::time;
 
-// #[derive(Debug)]
-// enum Cubes {
-//     Red,
-//     Green,
-//     Blue,
-// }
-//
+/// The bag of part one, any colours and counts work.
+const BAG: &str = "12 red, 13 green, 14 blue";
 
 #[derive(Debug, PartialEq, Eq)]
-struct Outcome {
-    nred: usize,
-    ngreen: usize,
-    nblue: usize,
+struct Game {
+    id: usize,
+    outcomes: Vec<Multiset>,
 }
 
-impl FromStr for Outcome {
-    type Err = Box<dyn Error + 'static>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"(?<number>\d+) (?<color>red|blue|green)",).unwrap();
-        }
-
-        let cap = RE.captures(s).ok_or("Not a valid box description")?;
-
-        let (mut nred, mut ngreen, mut nblue) = (0, 0, 0);
-
-        for b in RE.captures_iter(s) {
-            let num: usize = b
-                .name("number")
-                .ok_or("no number match")?
-                .as_str()
-                .parse()?;
-            match b.name("color").ok_or("no color match")?.as_str() {
-                "red" => nred += num,
-                "blue" => nblue += num,
-                "green" => ngreen += num,
-                _ => Err("Nooo")?,
-            };
-        }
-
-        Ok(Self {
-            nred,
-            ngreen,
-            nblue,
-        })
+impl Game {
+    /// Whether every outcome could have been drawn from `bag`.
+    fn fits(&self, bag: &Multiset) -> bool {
+        self.outcomes.iter().all(|o| o.is_subset(bag))
     }
-}
 
-#[derive(Debug, PartialEq, Eq)]
-struct Game {
-    id: usize,
-    outcomes: Vec<Outcome>,
+    /// The fewest cubes of each colour that make the game possible.
+    fn minimal_bag(&self) -> Multiset {
+        self.outcomes
+            .iter()
+            .fold(Multiset::new(), |bag, o| bag.union(o))
+    }
 }
 
 impl FromStr for Game {
@@ -67,9 +34,7 @@ impl FromStr for Game {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         lazy_static! {
-            static ref RE: Regex =
-                Regex::new(r"Game (?<gn>\d+):(?<results>(( \d+ (red|blue|green),?)+;?)+)",)
-                    .unwrap();
+            static ref RE: Regex = Regex::new(r"^Game (?<gn>\d+):(?<results>.*)$").unwrap();
         }
 
         let cap = RE.captures(s).ok_or("Not a valid game description")?;
@@ -86,9 +51,9 @@ impl FromStr for Game {
                 .name("results")
                 .ok_or("Wrong formatting")?
                 .as_str()
-                .split(";")
-                .map(|outcome| Outcome::from_str(outcome).unwrap())
-                .collect(),
+                .split(';')
+                .map(Multiset::from_str)
+                .collect::<Result<_, _>>()?,
         })
     }
 }
@@ -97,50 +62,32 @@ fn parse(input: &str) -> Vec<Game> {
     input.lines().map(|l| Game::from_str(l).unwrap()).collect()
 }
 
-fn part_one(input: &Vec<Game>) -> usize {
-    let mut n = 0;
-
-    'outer: for game in input {
-        for outcome in &game.outcomes {
-            if outcome.nred > 12 || outcome.ngreen > 13 || outcome.nblue > 14 {
-                continue 'outer;
-            }
-        }
-        n += game.id;
-    }
-
-    n
+fn part_one(input: &[Game], bag: &Multiset) -> usize {
+    input.iter().filter(|g| g.fits(bag)).map(|g| g.id).sum()
 }
 
-fn part_two(input: &Vec<Game>) -> usize {
-    let mut n = 0;
-
-    for game in input {
-        let (mut nred, mut ngreen, mut nblue) = (0, 0, 0);
-        for outcome in &game.outcomes {
-            nred = max(nred, outcome.nred);
-            ngreen = max(ngreen, outcome.ngreen);
-            nblue = max(nblue, outcome.nblue);
-        }
-
-        n += nred * ngreen * nblue;
-    }
-
-    n
+/// Sum of the powers of the minimal bags, the product of the counts of the colours in `bag`.
+fn part_two(input: &[Game], bag: &Multiset) -> usize {
+    let colours: Vec<&str> = bag.iter().map(|(colour, _)| colour).collect();
+    input
+        .iter()
+        .map(|g| g.minimal_bag().product(colours.iter().copied()))
+        .sum()
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
     let input = parse(&get_input("2023", "2"));
+    let bag: Multiset = BAG.parse()?;
 
     let now = time::Instant::now();
-    let sol_p1 = part_one(&input);
+    let sol_p1 = part_one(&input, &bag);
     println!(
         "Solution part one: {sol_p1} took: {}s",
         now.elapsed().as_secs_f32()
     );
 
     let now = time::Instant::now();
-    let sol_p2 = part_two(&input);
+    let sol_p2 = part_two(&input, &bag);
     println!(
         "Solution part two: {sol_p2} took: {}s",
         now.elapsed().as_secs_f32()
@@ -166,35 +113,42 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
             Game {
                 id: 1,
                 outcomes: vec![
-                    Outcome {
-                        nred: 4,
-                        ngreen: 0,
-                        nblue: 3
-                    },
-                    Outcome {
-                        nred: 1,
-                        ngreen: 2,
-                        nblue: 6
-                    },
-                    Outcome {
-                        nred: 0,
-                        ngreen: 2,
-                        nblue: 0
-                    }
+                    "4 red, 3 blue".parse().unwrap(),
+                    "1 red, 2 green, 6 blue".parse().unwrap(),
+                    "2 green".parse().unwrap(),
                 ]
             }
         );
+        assert!(Game::from_str("Game 1: 3 blue; four red").is_err());
     }
 
     #[test]
     fn test_part_one() {
         let game = parse(TEST);
-        assert_eq!(part_one(&game), 8);
+        assert_eq!(part_one(&game, &BAG.parse().unwrap()), 8);
     }
 
     #[test]
     fn test_part_two() {
         let game = parse(TEST);
-        assert_eq!(part_two(&game), 2286);
+        assert_eq!(part_two(&game, &BAG.parse().unwrap()), 2286);
+    }
+
+    #[test]
+    fn test_other_colours() {
+        let games = parse(
+            "Game 1: 2 purple, 1 red; 3 orange
+Game 2: 7 purple; 1 orange, 1 teal
+Game 3: 1 red, 1 orange",
+        );
+        let bag: Multiset = "5 purple, 3 orange, 1 red".parse().unwrap();
+        // game 2 has too many purple and no teal in the bag
+        assert_eq!(part_one(&games, &bag), 1 + 3);
+        assert_eq!(
+            games[1].minimal_bag(),
+            "7 purple, 1 orange, 1 teal".parse().unwrap()
+        );
+        // only game 1 has cubes of all the colours in the bag
+        assert_eq!(part_two(&games, &bag), 2 * 3);
     }
 }
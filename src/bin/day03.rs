@@ -1,197 +1,156 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-use rustvent2023::get_input;
+use rustvent2023::{get_input, Map, Pos};
 use std::collections::HashMap;
+use std::error::Error;
+use std::str::FromStr;
 use std::time;
 
-const NONSYMB: &str = ".01234567890";
-
-fn issymb(c: char) -> bool {
-    !NONSYMB.contains(c)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Number {
+    value: usize,
+    /// Position of the first digit.
+    pos: Pos,
+    /// Number of digits.
+    len: usize,
 }
 
-fn part_one(input: &str) -> usize {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"(\d+)").unwrap();
-    }
-
-    let lines: Vec<Vec<char>> = input.lines().map(|c| c.chars().collect()).collect();
-
-    let mut n = 0;
-
-    for (y, line) in input.lines().enumerate() {
-        'nums: for cap in RE.captures_iter(line) {
-            let num = cap[0].parse::<usize>().unwrap();
-            let range = cap.get(0).unwrap().range();
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Symbol {
+    kind: char,
+    pos: Pos,
+}
 
-            if range.start > 0 {
-                if issymb(lines[y][range.start - 1]) {
-                    n += num;
-                    continue 'nums;
-                }
-                if y > 0 && issymb(lines[y - 1][range.start - 1]) {
-                    n += num;
-                    continue 'nums;
-                }
-                if y < lines.len() - 1 && issymb(lines[y + 1][range.start - 1]) {
-                    n += num;
-                    continue 'nums;
-                }
-            }
+/// The engine schematic with its numbers, symbols and which of them touch, diagonals included.
+#[derive(Debug)]
+struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    /// Indices of the symbols around each number.
+    number_symbols: Vec<Vec<usize>>,
+    /// Indices of the numbers around each symbol.
+    symbol_numbers: Vec<Vec<usize>>,
+}
 
-            if range.end < lines[y].len() - 1 {
-                if issymb(lines[y][range.end]) {
-                    n += num;
-                    continue 'nums;
-                }
-                if y > 0 && issymb(lines[y - 1][range.end]) {
-                    n += num;
-                    continue 'nums;
-                }
-                if y < lines.len() - 1 && issymb(lines[y + 1][range.end]) {
-                    n += num;
-                    continue 'nums;
-                }
+impl FromStr for Schematic {
+    type Err = Box<dyn Error + 'static>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let map: Map<char> = s.parse()?;
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        for y in 0..map.height {
+            let row = map.row(y);
+            let mut x = 0;
+            while x < map.width {
+                let c = row[x];
+                if c.is_ascii_digit() {
+                    let len = row[x..].iter().take_while(|c| c.is_ascii_digit()).count();
+                    let digits: String = row[x..x + len].iter().collect();
+                    numbers.push(Number {
+                        value: digits.parse()?,
+                        pos: Pos::from((x as isize, y as isize)),
+                        len,
+                    });
+                    x += len;
+                    continue;
+                }
+                if c != '.' {
+                    symbols.push(Symbol {
+                        kind: c,
+                        pos: Pos::from((x as isize, y as isize)),
+                    });
+                }
+                x += 1;
             }
+        }
 
-            for x in range {
-                if y > 0 && issymb(lines[y - 1][x]) {
-                    n += num;
-                    continue 'nums;
-                }
-                if y < lines.len() - 1 && issymb(lines[y + 1][x]) {
-                    n += num;
-                    continue 'nums;
+        let symbol_at: HashMap<&Pos, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (&s.pos, i))
+            .collect();
+        let mut number_symbols = vec![Vec::new(); numbers.len()];
+        let mut symbol_numbers = vec![Vec::new(); symbols.len()];
+        for (n, number) in numbers.iter().enumerate() {
+            // the frame around the digits, cells outside the map never hold symbols
+            for y in number.pos.y - 1..=number.pos.y + 1 {
+                for x in number.pos.x - 1..=number.pos.x + number.len as isize {
+                    if let Some(&s) = symbol_at.get(&Pos::from((x, y))) {
+                        number_symbols[n].push(s);
+                        symbol_numbers[s].push(n);
+                    }
                 }
             }
         }
-    }
 
-    n
+        Ok(Schematic {
+            numbers,
+            symbols,
+            number_symbols,
+            symbol_numbers,
+        })
+    }
 }
 
-fn part_two(input: &str) -> usize {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"(\d+)").unwrap();
+impl Schematic {
+    /// Numbers next to a symbol whose kind matches, each only once.
+    fn parts_next_to<'a, P: Fn(char) -> bool + 'a>(
+        &'a self,
+        matches: P,
+    ) -> impl Iterator<Item = &'a Number> + 'a {
+        self.numbers
+            .iter()
+            .zip(&self.number_symbols)
+            .filter(move |(_, symbols)| symbols.iter().any(|&s| matches(self.symbols[s].kind)))
+            .map(|(n, _)| n)
     }
 
-    let lines: Vec<Vec<char>> = input.lines().map(|c| c.chars().collect()).collect();
-
-    let mut n = 0;
-
-    let mut gears: HashMap<(usize, usize), usize> = HashMap::new();
-
-    for (y, line) in input.lines().enumerate() {
-        'nums: for cap in RE.captures_iter(line) {
-            let num = cap[0].parse::<usize>().unwrap();
-            let range = cap.get(0).unwrap().range();
-
-            if range.start > 0 {
-                if '*' == (lines[y][range.start - 1]) {
-                    let idx = (y, range.start - 1);
-                    match gears.get(&idx) {
-                        Some(val) => n += num * val,
-                        None => {
-                            gears.insert(idx, num);
-                        }
-                    }
-                    continue 'nums;
-                }
-                if y > 0 && '*' == (lines[y - 1][range.start - 1]) {
-                    let idx = (y - 1, range.start - 1);
-                    match gears.get(&idx) {
-                        Some(val) => n += num * val,
-                        None => {
-                            gears.insert(idx, num);
-                        }
-                    }
-                    continue 'nums;
-                }
-                if y < lines.len() - 1 && '*' == (lines[y + 1][range.start - 1]) {
-                    let idx = (y + 1, range.start - 1);
-                    match gears.get(&idx) {
-                        Some(val) => n += num * val,
-                        None => {
-                            gears.insert(idx, num);
-                        }
-                    }
-                    continue 'nums;
-                }
-            }
+    /// The numbers around the symbol with index `symbol`.
+    fn neighbors(&self, symbol: usize) -> impl Iterator<Item = &Number> + '_ {
+        self.symbol_numbers[symbol]
+            .iter()
+            .map(|&n| &self.numbers[n])
+    }
 
-            if range.end < lines[y].len() - 1 {
-                if '*' == (lines[y][range.end]) {
-                    let idx = (y, range.end);
-                    match gears.get(&idx) {
-                        Some(val) => n += num * val,
-                        None => {
-                            gears.insert(idx, num);
-                        }
-                    }
-                    continue 'nums;
-                }
-                if y > 0 && '*' == (lines[y - 1][range.end]) {
-                    let idx = (y - 1, range.end);
-                    match gears.get(&idx) {
-                        Some(val) => n += num * val,
-                        None => {
-                            gears.insert(idx, num);
-                        }
-                    }
-                    continue 'nums;
-                }
-                if y < lines.len() - 1 && '*' == (lines[y + 1][range.end]) {
-                    let idx = (y + 1, range.end);
-                    match gears.get(&idx) {
-                        Some(val) => n += num * val,
-                        None => {
-                            gears.insert(idx, num);
-                        }
-                    }
-                    continue 'nums;
-                }
-            }
+    /// Symbols of `kind` with exactly `count` numbers around them, with their index.
+    fn symbols_with(
+        &self,
+        kind: char,
+        count: usize,
+    ) -> impl Iterator<Item = (usize, &Symbol)> + '_ {
+        self.symbols
+            .iter()
+            .enumerate()
+            .filter(move |&(i, s)| s.kind == kind && self.symbol_numbers[i].len() == count)
+    }
 
-            for x in range {
-                if y > 0 && '*' == (lines[y - 1][x]) {
-                    let idx = (y - 1, x);
-                    match gears.get(&idx) {
-                        Some(val) => n += num * val,
-                        None => {
-                            gears.insert(idx, num);
-                        }
-                    }
-                    continue 'nums;
-                }
-                if y < lines.len() - 1 && '*' == (lines[y + 1][x]) {
-                    let idx = (y + 1, x);
-                    match gears.get(&idx) {
-                        Some(val) => n += num * val,
-                        None => {
-                            gears.insert(idx, num);
-                        }
-                    }
-                    continue 'nums;
-                }
-            }
-        }
+    /// Products of the numbers around every gear,
+    /// a symbol of `kind` with exactly `count` numbers around it.
+    fn gear_ratios(&self, kind: char, count: usize) -> impl Iterator<Item = usize> + '_ {
+        self.symbols_with(kind, count)
+            .map(|(i, _)| self.neighbors(i).map(|n| n.value).product())
     }
+}
+
+fn part_one(schematic: &Schematic) -> usize {
+    schematic.parts_next_to(|_| true).map(|n| n.value).sum()
+}
 
-    n
+fn part_two(schematic: &Schematic) -> usize {
+    schematic.gear_ratios('*', 2).sum()
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let input = &get_input("2023", "3");
+    let schematic: Schematic = get_input("2023", "3").parse()?;
 
     let now = time::Instant::now();
-    let sol_p1 = part_one(&input);
+    let sol_p1 = part_one(&schematic);
     println!(
         "Solution part one: {sol_p1} took: {}s",
         now.elapsed().as_secs_f32()
     );
 
     let now = time::Instant::now();
-    let sol_p2 = part_two(&input);
+    let sol_p2 = part_two(&schematic);
     println!(
         "Solution part two: {sol_p2} took: {}s",
         now.elapsed().as_secs_f32()
@@ -215,13 +174,66 @@ mod tests {
 ...$.*....
 .664.598..";
 
+    #[test]
+    fn test_parse() {
+        let schematic: Schematic = TEST.parse().unwrap();
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(
+            schematic.numbers[1],
+            Number {
+                value: 114,
+                pos: (5, 0).into(),
+                len: 3
+            }
+        );
+        assert_eq!(schematic.symbols.len(), 6);
+        assert_eq!(schematic.symbols[1].kind, '#');
+    }
+
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(TEST), 4361);
+        assert_eq!(part_one(&TEST.parse().unwrap()), 4361);
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(&TEST), 467835);
+        assert_eq!(part_two(&TEST.parse().unwrap()), 467835);
+    }
+
+    #[test]
+    fn test_edges() {
+        // numbers touching the right edge used to be skipped
+        let schematic: Schematic = "..12\n.*..\n..34\n#..5".parse().unwrap();
+        assert_eq!(part_one(&schematic), 12 + 34);
+        assert_eq!(part_two(&schematic), 12 * 34);
+        assert_eq!(
+            schematic
+                .parts_next_to(|c| c == '#')
+                .map(|n| n.value)
+                .collect::<Vec<_>>(),
+            vec![] as Vec<usize>
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let schematic: Schematic = TEST.parse().unwrap();
+        let values = |numbers: Vec<&Number>| numbers.iter().map(|n| n.value).collect::<Vec<_>>();
+
+        assert_eq!(
+            values(schematic.parts_next_to(|c| c == '*').collect()),
+            vec![467, 35, 617, 755, 598]
+        );
+        assert_eq!(
+            values(schematic.parts_next_to(|c| c == '$').collect()),
+            vec![664]
+        );
+        let lonely: Vec<Pos> = schematic.symbols_with('*', 1).map(|(_, s)| s.pos).collect();
+        assert_eq!(lonely, vec![Pos::from((3, 4))]);
+
+        // a gear with three parts instead of two
+        let schematic: Schematic = "2.3\n.*.\n..4".parse().unwrap();
+        assert_eq!(schematic.gear_ratios('*', 2).count(), 0);
+        assert_eq!(schematic.gear_ratios('*', 3).collect::<Vec<_>>(), vec![24]);
     }
 }
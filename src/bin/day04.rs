@@ -1,99 +1,36 @@
-use lazy_static::lazy_static;
-use regex::Regex;
 use rustvent2023::get_input;
+use rustvent2023::scratchcard::{Cascade, Payout, Scratchcard};
 use std::error::Error;
 use std::str::FromStr;
 use std::time;
-use std::cmp::min;
 
-#[derive(Debug, PartialEq, Clone)]
-struct Card {
-    id: usize,
-    winning: Vec<usize>,
-    numbers: Vec<usize>,
+fn parse(input: &str) -> Result<Vec<Scratchcard>, Box<dyn Error + 'static>> {
+    input.lines().map(Scratchcard::from_str).collect()
 }
 
-impl FromStr for Card {
-    type Err = Box<dyn Error + 'static>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        lazy_static! {
-            static ref RE: Regex =
-                Regex::new(r"Card +(?<id>\d+): (?<winning>[\d ]+) \| (?<numbers>[\d ]+)").unwrap();
-        }
-
-        let cap = RE.captures(s).ok_or("Not a valid card description.")?;
-
-        let id = cap.name("id").unwrap().as_str().parse().unwrap();
-        let winning = cap
-            .name("winning")
-            .unwrap()
-            .as_str()
-            .trim()
-            .split(" ")
-            .filter_map(|s| s.parse().ok())
-            .collect();
-        let numbers = cap
-            .name("numbers")
-            .unwrap()
-            .as_str()
-            .trim()
-            .split(" ")
-            .filter_map(|s| s.parse().ok())
-            .collect();
-
-        Ok(Card {
-            id,
-            winning,
-            numbers,
-        })
-    }
-}
-
-fn parse(input: &str) -> Vec<Card> {
-    input.lines().map(|l| Card::from_str(l).unwrap()).collect()
+fn part_one(input: &[Scratchcard], payout: &Payout) -> Result<usize, Box<dyn Error + 'static>> {
+    input.iter().try_fold(0usize, |acc, card| {
+        acc.checked_add(payout.points(card.matches())?)
+            .ok_or_else(|| "Too many points in total".into())
+    })
 }
 
-fn part_one(input: &Vec<Card>) -> usize {
-    input
-        .iter()
-        .map(|card| {
-            card.numbers
-                .iter()
-                .filter(|n| card.winning.contains(n))
-                .fold(1, |acc, _x| acc * 2)/2
-        })
-        .sum()
-}
-
-fn part_two(input: &Vec<Card>) -> usize {
-    let mut instances = vec![1; input.len()];
-    for (i, card) in input.iter().enumerate() {
-        let wins = card.numbers
-            .iter()
-            .filter(|n| card.winning.contains(n))
-            .count();
-
-        for j in min(i+1, instances.len())..min(i+wins+1, instances.len()) {
-            instances[j] += instances[i];
-        }
-    }
-
-    instances.iter().sum()
+fn part_two(input: &[Scratchcard]) -> Result<usize, Box<dyn Error + 'static>> {
+    Cascade::simulate(input)?.total()
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let input = parse(&get_input("2023", "4"));
+    let input = parse(&get_input("2023", "4"))?;
 
     let now = time::Instant::now();
-    let sol_p1 = part_one(&input);
+    let sol_p1 = part_one(&input, &Payout::Doubling)?;
     println!(
         "Solution part one: {sol_p1} took: {}s",
         now.elapsed().as_secs_f32()
     );
 
     let now = time::Instant::now();
-    let sol_p2 = part_two(&input);
+    let sol_p2 = part_two(&input)?;
     println!(
         "Solution part two: {sol_p2} took: {}s",
         now.elapsed().as_secs_f32()
@@ -115,25 +52,55 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     fn test_parse() {
-        assert_eq!(
-            parse(TEST)[0],
-            Card {
-                id: 1,
-                winning: vec![41, 48, 83, 86, 17],
-                numbers: vec![83, 86, 6, 31, 17, 9, 48, 53]
-            }
-        );
+        let card = &parse(TEST).unwrap()[0];
+        assert_eq!(card.id, 1);
+        assert_eq!(card.winning, "41 48 83 86 17".parse().unwrap());
+        assert_eq!(card.numbers, "83 86 6 31 17 9 48 53".parse().unwrap());
+        assert!(parse("Card 1: 41 48 | 83 x").is_err());
     }
 
     #[test]
     fn test_part_one() {
-        let input = parse(TEST);
-        assert_eq!(part_one(&input), 13);
+        let input = parse(TEST).unwrap();
+        assert_eq!(part_one(&input, &Payout::Doubling).unwrap(), 13);
+    }
+
+    #[test]
+    fn test_part_one_overflow() {
+        // 64 matches are worth 2^63 points, twice that overflows
+        let numbers: Vec<String> = (1..=64).map(|n| n.to_string()).collect();
+        let numbers = numbers.join(" ");
+        let card = format!("Card 1: {numbers} | {numbers}");
+        let input = parse(&format!("{card}\n{card}")).unwrap();
+        assert_eq!(part_one(&input[..1], &Payout::Doubling).unwrap(), 1 << 63);
+        assert!(part_one(&input, &Payout::Doubling).is_err());
+    }
+
+    #[test]
+    fn test_payouts() {
+        let input = parse(TEST).unwrap();
+        // matches are 4, 2, 2, 1, 0, 0
+        assert_eq!(part_one(&input, &Payout::Linear).unwrap(), 9);
+        assert_eq!(
+            part_one(&input, &Payout::Table(vec![0, 5, 10])).unwrap(),
+            10 + 10 + 10 + 5
+        );
     }
 
     #[test]
     fn test_part_two() {
-        let input = parse(TEST);
-        assert_eq!(part_two(&input), 30);
+        let input = parse(TEST).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 30);
+    }
+
+    #[test]
+    fn test_cascade() {
+        let cascade = Cascade::simulate(&parse(TEST).unwrap()).unwrap();
+        assert_eq!(cascade.copies(), &[1, 2, 4, 8, 14, 1]);
+        // every original card's tree holds what it adds to the total
+        assert_eq!(
+            (0..6).map(|i| cascade.tree(i).size()).sum::<usize>(),
+            cascade.total().unwrap()
+        );
     }
 }
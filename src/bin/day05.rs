@@ -1,126 +1,69 @@
-use rustvent2023::get_input;
+use rustvent2023::piecewise::{Piece, PiecewiseMap};
+use rustvent2023::{get_input, IntervalSet};
+use std::collections::HashMap;
 use std::error::Error;
-use std::ops::{Range, RangeBounds};
+use std::ops::Range;
 use std::str::FromStr;
 use std::time;
 
-fn containment<Idx: PartialOrd + Copy>(
-    inner: &Range<Idx>,
-    bounding: &Range<Idx>,
-) -> (Option<Range<Idx>>, Vec<Range<Idx>>) {
-    if (inner.start < bounding.start) & (inner.end > bounding.end) {
-        (
-            Some(bounding.clone()),
-            vec![
-                Range {
-                    start: inner.start,
-                    end: bounding.start,
-                },
-                Range {
-                    start: bounding.end,
-                    end: inner.end,
-                },
-            ],
-        )
-    } else if bounding.contains(&inner.start) & bounding.contains(&inner.end) {
-        (Some(inner.clone()), vec![])
-    } else if bounding.contains(&inner.start) {
-        (
-            Some(Range {
-                start: inner.start,
-                end: bounding.end,
-            }),
-            vec![Range {
-                start: bounding.end,
-                end: inner.end,
-            }],
-        )
-    } else if bounding.contains(&inner.end) {
-        (
-            Some(Range {
-                start: bounding.start,
-                end: inner.end,
-            }),
-            vec![Range {
-                start: inner.start,
-                end: bounding.start,
-            }],
-        )
-    } else {
-        (None, vec![inner.clone()])
-    }
-}
-
 #[derive(Debug, PartialEq, Clone)]
 struct Mapping {
+    /// Source category, like `seed`.
+    from: String,
+    /// Destination category, like `soil`.
+    to: String,
     ranges: Vec<(Range<usize>, Range<usize>)>,
+    /// The ranges as one map, built when parsing.
+    map: PiecewiseMap,
 }
 
 impl FromStr for Mapping {
     type Err = Box<dyn Error + 'static>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let ranges = s
-            .lines()
-            .skip(1)
+        let mut lines = s.lines();
+        let header = lines.next().ok_or("Empty mapping")?;
+        let (from, to) = header
+            .strip_suffix(" map:")
+            .and_then(|names| names.split_once("-to-"))
+            .ok_or_else(|| format!("Invalid mapping header {header:?}"))?;
+
+        let ranges: Vec<(Range<usize>, Range<usize>)> = lines
             .map(|l| {
                 let nums: Vec<usize> = l
                     .split_whitespace()
                     .map(str::parse)
-                    .collect::<Result<Vec<_>, _>>()
-                    .unwrap();
+                    .collect::<Result<Vec<_>, _>>()?;
+                let &[dest, src, len] = nums.as_slice() else {
+                    return Err(format!("Expected three numbers, got {l:?}").into());
+                };
 
-                (
+                let too_long = || format!("Range {l:?} exceeds usize");
+                Ok((
                     Range {
-                        start: nums[1],
-                        end: nums[1] + nums[2],
+                        start: src,
+                        end: src.checked_add(len).ok_or_else(too_long)?,
                     },
                     Range {
-                        start: nums[0],
-                        end: nums[0] + nums[2],
+                        start: dest,
+                        end: dest.checked_add(len).ok_or_else(too_long)?,
                     },
-                )
+                ))
             })
-            .collect();
-
-        Ok(Self { ranges })
-    }
-}
-
-impl Mapping {
-    fn map(&self, num: usize) -> usize {
-        for (from, to) in &self.ranges {
-            if from.contains(&num) {
-                return to.start + num - from.start;
-            }
-        }
-
-        num
-    }
-
-    fn map_ranges(&self, mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
-        let mut mapped = Vec::new();
-
-        let mut new_unmapped;
-        for (from, to) in &self.ranges {
-            new_unmapped = Vec::new();
-            for um in ranges {
-                let (inner, out) = containment(&um, &from);
-
-                new_unmapped.extend(out);
-
-                if let Some(inner) = inner {
-                    mapped.push(Range {
-                        start: to.start + inner.start - from.start,
-                        end: to.start + inner.end - from.start,
-                    });
-                }
-            }
-            ranges = new_unmapped;
-        }
-
-        mapped.extend(ranges);
-        mapped
+            .collect::<Result<_, Box<dyn Error + 'static>>>()?;
+
+        // rejects overlapping sources
+        let map = PiecewiseMap::from_pieces(ranges.iter().map(|(from, to)| Piece {
+            src: from.clone(),
+            dest: to.start,
+        }))?;
+
+        Ok(Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            ranges,
+            map,
+        })
     }
 }
 
@@ -145,55 +88,134 @@ impl FromStr for Game {
             .map(str::parse)
             .collect::<Result<_, _>>()?;
 
-        let mappings = segments.map(Mapping::from_str).collect::<Result<_, _>>()?;
+        let mappings: Vec<Mapping> = segments.map(Mapping::from_str).collect::<Result<_, _>>()?;
+        for (i, m) in mappings.iter().enumerate() {
+            if mappings[..i].iter().any(|other| other.from == m.from) {
+                return Err(format!("Two mappings from {:?}", m.from).into());
+            }
+        }
 
         Ok(Game { seeds, mappings })
     }
 }
 
-fn part_one(game: &Game) -> usize {
+impl Game {
+    /// The mappings leading from category `from` to category `to`, in order.
+    fn chain(&self, from: &str, to: &str) -> Result<Vec<&Mapping>, Box<dyn Error + 'static>> {
+        let by_source: HashMap<&str, &Mapping> =
+            self.mappings.iter().map(|m| (m.from.as_str(), m)).collect();
+        let mut chain = Vec::new();
+        let mut category = from;
+        while category != to {
+            let mapping = by_source
+                .get(category)
+                .ok_or_else(|| format!("No way from {from:?} to {to:?}"))?;
+            // every mapping is used at most once, otherwise the categories loop
+            if chain.len() == self.mappings.len() {
+                return Err(format!("Categories from {from:?} loop").into());
+            }
+            chain.push(*mapping);
+            category = &mapping.to;
+        }
+        Ok(chain)
+    }
+
+    /// All mappings from `from` to `to` in a row.
+    fn composed(&self, from: &str, to: &str) -> Result<PiecewiseMap, Box<dyn Error + 'static>> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .fold(PiecewiseMap::identity(), |acc, m| acc.then(&m.map)))
+    }
+
+    /// All values of category `from` that end up in `values` of the later category `to`.
+    fn reverse(
+        &self,
+        from: &str,
+        to: &str,
+        values: &IntervalSet<usize>,
+    ) -> Result<IntervalSet<usize>, Box<dyn Error + 'static>> {
+        Ok(self.composed(from, to)?.preimage(values))
+    }
+
+    /// The value at every category from `from` to `to`, both included.
+    fn trace<'a>(
+        &'a self,
+        from: &'a str,
+        to: &str,
+        value: usize,
+    ) -> Result<Vec<(&'a str, usize)>, Box<dyn Error + 'static>> {
+        let mut trace = vec![(from, value)];
+        for m in self.chain(from, to)? {
+            let (_, value) = trace[trace.len() - 1];
+            trace.push((&m.to, m.map.apply(value)));
+        }
+        Ok(trace)
+    }
+}
+
+fn part_one(game: &Game) -> Result<usize, Box<dyn Error + 'static>> {
+    let composed = game.composed("seed", "location")?;
     game.seeds
         .iter()
-        .map(|s| *&game.mappings.iter().fold(*s, |acc, map| map.map(acc)))
+        .map(|&s| composed.apply(s))
         .min()
-        .unwrap()
+        .ok_or_else(|| "No seeds".into())
 }
 
-fn part_two(game: &Game) -> usize {
-    game.seeds
+fn part_two(game: &Game) -> Result<usize, Box<dyn Error + 'static>> {
+    let seeds: IntervalSet<usize> = game
+        .seeds
         .chunks_exact(2)
         .map(|v| {
-            game.mappings.iter().fold(
-                vec![Range {
-                    start: v[0],
-                    end: v[0] + v[1],
-                }],
-                |acc, map| map.map_ranges(acc),
-            )
+            let end = v[0]
+                .checked_add(v[1])
+                .ok_or_else(|| format!("Seed range {} + {} overflows", v[0], v[1]))?;
+            Ok(v[0]..end)
         })
-        .flatten()
-        .map(|r| r.start)
+        .collect::<Result<_, Box<dyn Error + 'static>>>()?;
+
+    game.composed("seed", "location")?
+        .image(&seeds)
         .min()
-        .unwrap()
+        .ok_or_else(|| "No seeds".into())
+}
+
+/// All seeds, listed or not, that end up at `location`.
+fn seeds_at(game: &Game, location: usize) -> Result<IntervalSet<usize>, Box<dyn Error + 'static>> {
+    game.reverse("seed", "location", &(location..location + 1).into())
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
     let input = Game::from_str(&get_input("2023", "5"))?;
 
     let now = time::Instant::now();
-    let sol_p1 = part_one(&input);
+    let sol_p1 = part_one(&input)?;
     println!(
         "Solution part one: {sol_p1} took: {}μs",
         now.elapsed().as_micros()
     );
 
     let now = time::Instant::now();
-    let sol_p2 = part_two(&input);
+    let sol_p2 = part_two(&input)?;
     println!(
         "Solution part two: {sol_p2} took: {}μs",
         now.elapsed().as_micros()
     );
 
+    println!(
+        "Seeds ending up at location {sol_p2}: {:?}",
+        seeds_at(&input, sol_p2)?.ranges()
+    );
+    if let Some(&seed) = input.seeds.first() {
+        let trace: Vec<String> = input
+            .trace("seed", "location", seed)?
+            .iter()
+            .map(|(category, value)| format!("{category} {value}"))
+            .collect();
+        println!("{}", trace.join(" -> "));
+    }
+
     Ok(())
 }
 
@@ -240,17 +262,109 @@ humidity-to-location map:
         let game = Game::from_str(TEST).unwrap();
         assert_eq!(game.seeds, vec![79, 14, 55, 13]);
         assert_eq!(game.mappings[6].ranges[0].1, Range { start: 60, end: 97 });
+        assert_eq!(
+            (game.mappings[6].from.as_str(), game.mappings[6].to.as_str()),
+            ("humidity", "location")
+        );
+
+        assert!(Mapping::from_str("seed-soil map:\n1 2 3").is_err());
+        assert!(Mapping::from_str("seed-to-soil map:\n1 2").is_err());
+        assert!(Mapping::from_str("seed-to-soil map:\n1 2 x").is_err());
+        // sources 10..15 and 12..20 overlap
+        assert!(Mapping::from_str("seed-to-soil map:\n50 10 5\n80 12 8").is_err());
+        assert!(Mapping::from_str(&format!("seed-to-soil map:\n0 {} 2", usize::MAX)).is_err());
     }
 
     #[test]
     fn test_part_one() {
         let game = Game::from_str(TEST).unwrap();
-        assert_eq!(part_one(&game), 35);
+        assert_eq!(part_one(&game).unwrap(), 35);
     }
 
     #[test]
     fn test_part_two() {
         let game = Game::from_str(TEST).unwrap();
-        assert_eq!(part_two(&game), 46);
+        assert_eq!(part_two(&game).unwrap(), 46);
+    }
+
+    #[test]
+    fn test_seed_range_overflow() {
+        let mut game = Game::from_str(TEST).unwrap();
+        game.seeds = vec![usize::MAX - 1, 2];
+        assert!(part_two(&game).is_err());
+        game.seeds = vec![usize::MAX - 1, 1];
+        assert_eq!(part_two(&game).unwrap(), usize::MAX - 1);
+    }
+
+    #[test]
+    fn test_composed() {
+        let game = Game::from_str(TEST).unwrap();
+        let composed = game.composed("seed", "location").unwrap();
+        for seed in 0..120 {
+            let location = game.mappings.iter().fold(seed, |acc, m| m.map.apply(acc));
+            assert_eq!(composed.apply(seed), location);
+        }
+    }
+
+    #[test]
+    fn test_seeds_at() {
+        let game = Game::from_str(TEST).unwrap();
+        assert!(seeds_at(&game, 82).unwrap().contains(&79));
+        assert!(seeds_at(&game, 46).unwrap().contains(&82));
+        for seed in seeds_at(&game, 35).unwrap().iter().flat_map(|r| r.clone()) {
+            assert_eq!(game.composed("seed", "location").unwrap().apply(seed), 35);
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let game = Game::from_str(TEST).unwrap();
+        assert_eq!(
+            game.trace("seed", "location", 79).unwrap(),
+            vec![
+                ("seed", 79),
+                ("soil", 81),
+                ("fertilizer", 81),
+                ("water", 81),
+                ("light", 74),
+                ("temperature", 78),
+                ("humidity", 78),
+                ("location", 82)
+            ]
+        );
+        assert_eq!(
+            game.trace("water", "temperature", 81).unwrap(),
+            vec![("water", 81), ("light", 74), ("temperature", 78)]
+        );
+        assert_eq!(game.trace("soil", "soil", 5).unwrap(), vec![("soil", 5)]);
+        // only forward, and only between known categories
+        assert!(game.trace("location", "seed", 82).is_err());
+        assert!(game.trace("seed", "banana", 1).is_err());
+    }
+
+    #[test]
+    fn test_reverse() {
+        let game = Game::from_str(TEST).unwrap();
+        // soil 81 comes from seed 79 only
+        let seeds = game.reverse("seed", "soil", &(81..82).into()).unwrap();
+        assert_eq!(
+            seeds.iter().flat_map(|r| r.clone()).collect::<Vec<_>>(),
+            vec![79]
+        );
+        let lights = game.reverse("light", "humidity", &(78..79).into()).unwrap();
+        assert!(lights.contains(&74));
+
+        // shuffled sections are chained by their names, not their order
+        let mut sections: Vec<&str> = TEST.split("\n\n").collect();
+        sections[1..].reverse();
+        let shuffled = Game::from_str(&sections.join("\n\n")).unwrap();
+        assert_eq!(part_one(&shuffled).unwrap(), 35);
+        assert_eq!(part_two(&shuffled).unwrap(), 46);
+
+        let looping = "seeds: 1\n\na-to-b map:\n1 2 3\n\nb-to-a map:\n1 2 3";
+        let looping = Game::from_str(looping).unwrap();
+        assert!(looping.chain("a", "c").is_err());
+        assert_eq!(looping.chain("a", "a").unwrap().len(), 0);
+        assert!(Game::from_str("seeds: 1\n\na-to-b map:\n\na-to-c map:").is_err());
     }
 }
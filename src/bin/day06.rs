@@ -1,7 +1,8 @@
 use rustvent2023::get_input;
-use std::{f64, time};
+use rustvent2023::race::{Linear, RaceModel};
+use std::time;
 
-fn parse(input: &str) -> (Vec<usize>, Vec<usize>) {
+fn parse(input: &str) -> (Vec<u64>, Vec<u64>) {
     let mut l = input.lines();
 
     (
@@ -22,7 +23,7 @@ fn parse(input: &str) -> (Vec<usize>, Vec<usize>) {
     )
 }
 
-fn parse_part2(input: &str) -> (usize, usize) {
+fn parse_part2(input: &str) -> (u64, u64) {
     let mut l = input.lines();
 
     (
@@ -43,25 +44,15 @@ fn parse_part2(input: &str) -> (usize, usize) {
     )
 }
 
-fn part_one(time: &Vec<usize>, dist: &Vec<usize>) -> usize {
+fn part_one<M: RaceModel>(model: &M, time: &[u64], dist: &[u64]) -> u64 {
     time.iter()
         .zip(dist.iter())
-        .map(|(t, d)| {
-            let mut n = 0;
-            for h in 1..*t {
-                if h * (t - h) > *d {
-                    n += 1;
-                }
-            }
-            n
-        })
+        .map(|(&t, &d)| model.count_wins(t, d))
         .product()
 }
 
-// turns out, this problem has an analytical solution
-// quadratic formulas rock
-fn part_two(time: usize, dist: usize) -> usize {
-    2 * ((time as f64 * time as f64 / 4.0 - dist as f64).sqrt() as usize)
+fn part_two<M: RaceModel>(model: &M, time: u64, dist: u64) -> u64 {
+    model.count_wins(time, dist)
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
@@ -69,7 +60,7 @@ fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
     let (time, dist) = parse(&input);
 
     let now = time::Instant::now();
-    let sol_p1 = part_one(&time, &dist);
+    let sol_p1 = part_one(&Linear, &time, &dist);
     println!(
         "Solution part one: {sol_p1} took: {}μs",
         now.elapsed().as_micros()
@@ -77,7 +68,7 @@ fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
 
     let (time, dist) = parse_part2(&input);
     let now = time::Instant::now();
-    let sol_p2 = part_two(time, dist);
+    let sol_p2 = part_two(&Linear, time, dist);
     println!(
         "Solution part two: {sol_p2} took: {}μs",
         now.elapsed().as_micros()
@@ -110,12 +101,29 @@ Distance:  9  40  200";
     #[test]
     fn test_part_one() {
         let (time, dist) = parse(TEST);
-        assert_eq!(part_one(&time, &dist), 288);
+        assert_eq!(part_one(&Linear, &time, &dist), 288);
     }
 
     #[test]
     fn test_part_two() {
         let (time, dist) = parse_part2(TEST);
-        assert_eq!(part_two(time, dist), 71503);
+        assert_eq!(part_two(&Linear, time, dist), 71503);
+    }
+
+    #[test]
+    fn test_what_if() {
+        use rustvent2023::race::{Accelerating, Capped, ChargeCost};
+
+        let (time, dist) = parse(TEST);
+        // a top speed of 7 mm/ms covers at most 7 * 23 mm in the last race
+        assert_eq!(part_one(&Capped { max_speed: 7 }, &time, &dist), 0);
+        assert_eq!(part_one(&Capped { max_speed: 15 }, &time, &dist), 4 * 8 * 6);
+        assert_eq!(part_one(&Accelerating, &time, &dist), 5 * 13 * 27);
+        assert_eq!(part_one(&ChargeCost { cost: 1 }, &time, &dist), 0);
+
+        let (time, dist) = parse_part2(TEST);
+        assert_eq!(Linear.optimal_hold(time), 35765);
+        assert_eq!(Accelerating.optimal_hold(time), 47687);
+        assert_eq!(part_two(&ChargeCost { cost: 1 }, time, dist), 35738);
     }
 }
@@ -1,8 +1,11 @@
 use lazy_static::lazy_static;
 use regex::Regex;
+use rustvent2023::cycle::{self, Orbit};
 use rustvent2023::get_input;
+use rustvent2023::math::{crt, gcd};
 use std::collections::HashMap;
-use std::{time, usize};
+use std::error::Error;
+use std::time;
 
 type Map<'a> = HashMap<&'a str, (&'a str, &'a str)>;
 
@@ -12,7 +15,7 @@ enum Dir {
     R,
 }
 
-fn parse(input: &str) -> (Vec<Dir>, Map) {
+fn parse(input: &str) -> (Vec<Dir>, Map<'_>) {
     let mut parts = input.split("\n\n");
 
     let dirs = parts
@@ -51,7 +54,7 @@ fn parse(input: &str) -> (Vec<Dir>, Map) {
     (dirs, map)
 }
 
-fn part_one(dirs: &Vec<Dir>, map: &Map) -> usize {
+fn part_one(dirs: &[Dir], map: &Map) -> usize {
     let mut loc = "AAA";
 
     let mut n = 0;
@@ -70,121 +73,83 @@ fn part_one(dirs: &Vec<Dir>, map: &Map) -> usize {
     n
 }
 
-fn part_two(dirs: &Vec<Dir>, map: &Map) -> usize {
-    let mut locs: Vec<_> = map
-        .keys()
-        .filter(|c| c.chars().nth(2).unwrap() == 'A')
-        .map(|c| *c)
-        .collect();
-
-    let rep = 5000;
+/// The steps of one ghost from `start`, where the state is the node
+/// and the position in the directions.
+fn ghost_orbit<'a>(dirs: &[Dir], map: &Map<'a>, start: &'a str) -> Orbit<(&'a str, usize)> {
+    let next = |&(loc, i): &(&'a str, usize)| {
+        let next = match dirs[i] {
+            Dir::L => map[loc].0,
+            Dir::R => map[loc].1,
+        };
+        (next, (i + 1) % dirs.len())
+    };
+    cycle::hashed((start, 0), next).orbit((start, 0), next, |(loc, _)| loc.ends_with('Z'))
+}
 
-    let finals: HashMap<&str, (Vec<usize>, &str)> = map
+/// First step at which all ghosts stand on a `Z` node at once, `None` if they never do.
+/// Fails if that step doesn't fit into an `i64`.
+fn part_two(dirs: &[Dir], map: &Map) -> Result<Option<usize>, Box<dyn Error + 'static>> {
+    let orbits: Vec<_> = map
         .keys()
-        .map(|m| {
-            let mut fins = Vec::new();
-
-            let mut loc = *m;
-
-            let mut n = 0;
-            for _ in 0..rep {
-                for dir in dirs {
-                    loc = match dir {
-                        Dir::L => map[loc].0,
-                        Dir::R => map[loc].1,
-                    };
-                    if loc.chars().nth(2).unwrap() == 'Z' {
-                        fins.push(n);
-                    }
-                    n += 1;
-                }
-            }
-
-            (*m, (fins, loc))
-        })
+        .filter(|c| c.ends_with('A'))
+        .map(|start| ghost_orbit(dirs, map, start))
         .collect();
 
-    let mut n = 0;
-    let mut found = false;
-    while !found {
-        for fin in &finals[locs[0]].0 {
-            found = true;
-            for loc in locs.iter().skip(1) {
-                if !finals[loc].0.contains(&fin) {
-                    found = false;
-                    break;
-                }
-            }
-            if found {
-                n += fin + 1;
-                break;
-            }
-        }
-
-        if !found {
-            for loc in &mut locs {
-                *loc = finals[loc].1;
-            }
-            n += dirs.len() * rep;
-        }
-        print!("{}\r", n);
+    // before every ghost is in its cycle, just check each step
+    let Some(prefix) = orbits.iter().map(|o| o.cycle.start).max() else {
+        return Ok(None);
+    };
+    if let Some(n) = (0..prefix).find(|&n| orbits.iter().all(|o| o.is_hit(n))) {
+        return Ok(Some(n));
     }
-    n
-}
 
-fn euclid(mut a: usize, mut b: usize) -> usize {
-    while b > 0 {
-        let t = b;
-        b = a % b;
-        a = t;
-    }
-    a
-}
-
-fn lcm(a: usize, b: usize) -> usize {
-    a / euclid(a, b) * b
-}
-
-fn part_two_way2(dirs: &Vec<Dir>, map: &Map) -> usize {
-    let locs: Vec<_> = map
-        .keys()
-        .filter(|c| c.chars().nth(2).unwrap() == 'A')
-        .map(|c| *c)
+    let cycles: Vec<(Vec<usize>, usize)> = orbits
+        .iter()
+        .map(|o| (o.cycle_hits().collect(), o.cycle.len))
         .collect();
+    first_common_hit(prefix, &cycles)
+}
 
-    let paths: HashMap<&str, (usize, usize)> = locs
-        .iter() // (cycle_len, cycle_idx)
-        .map(|m| {
-            let mut loc = *m;
-            let mut visited = Vec::new();
-
-            let mut n = 0;
-            loop {
-                for (i, dir) in dirs.iter().enumerate() {
-                    visited.push((loc, i));
-                    n += 1;
-                    loc = match dir {
-                        Dir::L => map[loc].0,
-                        Dir::R => map[loc].1,
-                    };
-                    if let Some(i) = visited.iter().position(|&r| r == (loc, i + 1)) {
-                        return (*m, (n, i));
-                    }
+/// First step from `prefix` on that is a hit of every cycle, given as
+/// the steps it hits and its length. Fails if the combined cycle overflows.
+fn first_common_hit(
+    prefix: usize,
+    cycles: &[(Vec<usize>, usize)],
+) -> Result<Option<usize>, Box<dyn Error + 'static>> {
+    // every cycle hits on some residues modulo its length, try every combination of them
+    let mut combinations = vec![(0, 1)];
+    for (hits, len) in cycles {
+        let mut next = Vec::new();
+        for &(r, m) in &combinations {
+            for &h in hits {
+                // residues that never meet, unlike an overflow this only drops the combination
+                if (h as i64 - r) % gcd(m as usize, *len) as i64 != 0 {
+                    continue;
                 }
+                let combined = crt([(r, m), (h as i64, *len as i64)])
+                    .ok_or("The combined cycle of the ghosts is too long")?;
+                next.push(combined);
             }
-        })
-        .collect();
-
-    println!("{:#?}", paths);
-
-    let m: Vec<usize> = paths.values().map(|(v1, v2)| v1 - v2).collect();
-    // Least common multiple
-    let mut lcm_ = m[0];
-    for mi in m {
-        lcm_ = lcm(lcm_, mi);
+        }
+        combinations = next;
     }
 
-    lcm_
+    let mut first: Option<usize> = None;
+    for (r, m) in combinations {
+        let (r, m) = (r as usize, m as usize);
+        // smallest solution not before the prefix
+        let n = if r >= prefix {
+            r
+        } else {
+            (prefix - r)
+                .div_ceil(m)
+                .checked_mul(m)
+                .and_then(|k| k.checked_add(r))
+                .ok_or("The ghosts meet too late")?
+        };
+        first = Some(first.map_or(n, |f| f.min(n)));
+    }
+    Ok(first)
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
@@ -199,21 +164,12 @@ fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
     );
 
     let now = time::Instant::now();
-    let sol_p2 = part_two_way2(&dirs, &map);
+    let sol_p2 = part_two(&dirs, &map)?.ok_or("The ghosts never meet on Z nodes")?;
     println!(
-        "Solution part two in a smarter way: {sol_p2} took: {}μs",
+        "Solution part two: {sol_p2} took: {}μs",
         now.elapsed().as_micros()
     );
 
-    return Ok(());
-
-    let now = time::Instant::now();
-    let sol_p2 = part_two(&dirs, &map);
-    println!(
-        "Solution part two: {sol_p2} took: {}s",
-        now.elapsed().as_secs()
-    );
-
     Ok(())
 }
 
@@ -248,6 +204,31 @@ ZZZ = (ZZZ, ZZZ)
 22C = (22Z, 22Z)
 22Z = (22B, 22B)
 XXX = (XXX, XXX)
+";
+
+    const TEST4: &str = "L
+
+11A = (11B, XXX)
+11B = (11Z, XXX)
+11Z = (11C, XXX)
+11C = (11B, XXX)
+22A = (22Z, XXX)
+22Z = (22B, XXX)
+22B = (22Z, XXX)
+33A = (33B, XXX)
+33B = (33C, XXX)
+33C = (3DZ, XXX)
+3DZ = (3EZ, XXX)
+3EZ = (33B, XXX)
+XXX = (XXX, XXX)
+";
+
+    const TEST5: &str = "L
+
+44A = (4BZ, XXX)
+4BZ = (44C, XXX)
+44C = (44C, XXX)
+XXX = (XXX, XXX)
 ";
 
     #[test]
@@ -261,7 +242,6 @@ XXX = (XXX, XXX)
     #[test]
     fn test_part_one() {
         let (dirs, map) = parse(TEST);
-        println!("{:#?}", map);
         assert_eq!(part_one(&dirs, &map), 2);
 
         let (dirs, map) = parse(TEST2);
@@ -271,12 +251,53 @@ XXX = (XXX, XXX)
     #[test]
     fn test_part_two() {
         let (dirs, map) = parse(TEST3);
-        assert_eq!(part_two(&dirs, &map), 6);
+        assert_eq!(part_two(&dirs, &map).unwrap(), Some(6));
     }
 
     #[test]
-    fn test_part_two_smarter() {
-        let (dirs, map) = parse(TEST3);
-        assert_eq!(part_two_way2(&dirs, &map), 6);
+    fn test_part_two_phases() {
+        // 11A enters B -> Z -> C after one step, hitting Z at 2 mod 3,
+        // 22A hits Z on every odd step,
+        // 33A hits 3DZ and 3EZ at 3 and 0 mod 4
+        let (dirs, map) = parse(TEST4);
+        assert_eq!(part_two(&dirs, &map).unwrap(), Some(11));
+
+        let orbit = ghost_orbit(&dirs, &map, "33A");
+        assert_eq!(orbit.cycle, cycle::Cycle { start: 1, len: 4 });
+        assert_eq!(orbit.cycle_hits().collect::<Vec<_>>(), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_part_two_prefix() {
+        // 44A passes a Z node only once before getting stuck
+        let (dirs, map) = parse(TEST5);
+        assert_eq!(part_two(&dirs, &map).unwrap(), Some(1));
+
+        let input = format!("{TEST4}{}", &TEST5["L\n\n".len()..]);
+        let (dirs, map) = parse(&input);
+        assert_eq!(part_two(&dirs, &map).unwrap(), None);
+    }
+
+    #[test]
+    fn test_first_common_hit() {
+        assert_eq!(
+            first_common_hit(0, &[(vec![2], 3), (vec![1, 3], 4)]).unwrap(),
+            Some(5)
+        );
+        // incompatible residues are skipped, the others still meet
+        assert_eq!(
+            first_common_hit(0, &[(vec![0, 3], 4), (vec![1], 6)]).unwrap(),
+            Some(7)
+        );
+        assert_eq!(
+            first_common_hit(0, &[(vec![0], 4), (vec![1], 6)]).unwrap(),
+            None
+        );
+        assert_eq!(first_common_hit(7, &[(vec![1], 2)]).unwrap(), Some(7));
+
+        // the combined cycle doesn't fit into an i64, so there is no reliable minimum
+        let primes = [1_000_000_007, 1_000_000_009, 998_244_353];
+        let cycles: Vec<(Vec<usize>, usize)> = primes.iter().map(|&p| (vec![1], p)).collect();
+        assert!(first_common_hit(0, &cycles).is_err());
     }
 }
@@ -1,51 +1,52 @@
-use lazy_static::lazy_static;
-use regex::Regex;
 use rustvent2023::get_input;
-use std::collections::HashMap;
-use std::{time, usize};
+use rustvent2023::sequence::Sequence;
+use std::error::Error;
+use std::time;
 
+fn parse(input: &str) -> Vec<Vec<i64>> {
+    input
+        .lines()
+        .map(|l| l.split_whitespace().map(str::parse).collect())
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
 
-fn parse(input: &str) -> Vec<Vec<isize>> {
-    input.lines().map(|l| l.split_whitespace().map(str::parse).collect()).collect::<Result<_, _>>().unwrap()
+/// Sum of the values of every sequence at `index(samples)`.
+fn extrapolate<F>(nums: &[Vec<i64>], index: F) -> Result<i64, Box<dyn Error + 'static>>
+where
+    F: Fn(&[i64]) -> i64,
+{
+    nums.iter()
+        .map(|ns| {
+            let n = index(ns);
+            Sequence::from_samples(ns)?
+                .at(n)
+                .ok_or_else(|| format!("Value of {ns:?} at {n} overflows").into())
+        })
+        .sum()
 }
 
-fn part_one(nums: &Vec<Vec<isize>>) -> isize {
-    nums.iter().map(|ns| {
-        let mut vec = ns.clone();
-        let mut last = Vec::new();
-        while !vec.iter().all(|&n| n == 0){
-            last.push(*vec.last().unwrap());
-            vec = vec.windows(2).map(|w| w[1]-w[0]).collect();
-        }
-        last.iter().sum::<isize>()
-    }).sum()
+fn part_one(nums: &[Vec<i64>]) -> Result<i64, Box<dyn Error + 'static>> {
+    extrapolate(nums, |ns| ns.len() as i64)
 }
 
-fn part_two(nums: &Vec<Vec<isize>>) -> isize {
-    nums.iter().map(|ns| {
-        let mut vec = ns.clone();
-        let mut first = Vec::new();
-        while !vec.iter().all(|&n| n == 0){
-            first.push(*vec.first().unwrap());
-            vec = vec.windows(2).map(|w| w[1]-w[0]).collect();
-        }
-        first.iter().rev().fold(0, |acc, el| el-acc)
-    }).sum()
+fn part_two(nums: &[Vec<i64>]) -> Result<i64, Box<dyn Error + 'static>> {
+    extrapolate(nums, |_| -1)
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+fn main() -> Result<(), Box<dyn Error + 'static>> {
     let input = get_input("2023", "9");
     let nums = parse(&input);
 
     let now = time::Instant::now();
-    let sol_p1 = part_one(&nums);
+    let sol_p1 = part_one(&nums)?;
     println!(
         "Solution part one: {sol_p1} took: {}μs",
         now.elapsed().as_micros()
     );
 
     let now = time::Instant::now();
-    let sol_p2 = part_two(&nums);
+    let sol_p2 = part_two(&nums)?;
     println!(
         "Solution part two: {sol_p2} took: {}μs",
         now.elapsed().as_micros()
@@ -72,12 +73,20 @@ mod tests {
     #[test]
     fn test_part_one() {
         let nums = parse(TEST);
-        assert_eq!(part_one(&nums), 114);
+        assert_eq!(part_one(&nums).unwrap(), 114);
     }
 
     #[test]
     fn test_part_two() {
         let nums = parse(TEST);
-        assert_eq!(part_two(&nums), 2);
+        assert_eq!(part_two(&nums).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_differences_not_reaching_zero() {
+        // extrapolated through all differences, like any other line
+        let nums = parse("1 2 4 8 16\n");
+        assert_eq!(part_one(&nums).unwrap(), 31);
+        assert_eq!(part_two(&nums).unwrap(), 1);
     }
 }
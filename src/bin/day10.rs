@@ -1,9 +1,10 @@
-use rustvent2023::get_input;
-use std::convert::{From, Into, TryFrom};
+use rustvent2023::bitgrid::BitGrid;
+use rustvent2023::{get_input, Dir4, Map, Pos};
+use std::error::Error;
 use std::fmt;
 use std::time;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tile {
     Empty,
     Start,
@@ -31,26 +32,6 @@ impl fmt::Display for Tile {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-enum Dir {
-    North,
-    West,
-    South,
-    East,
-}
-
-impl Dir {
-    fn opp(&self) -> Dir {
-        use Dir::*;
-        match self {
-            North => South,
-            South => North,
-            West => East,
-            East => West,
-        }
-    }
-}
-
 impl TryFrom<char> for Tile {
     type Error = ();
 
@@ -71,8 +52,8 @@ impl TryFrom<char> for Tile {
 }
 
 impl Tile {
-    fn get_dirs(&self) -> Option<(Dir, Dir)> {
-        use Dir::*;
+    fn get_dirs(&self) -> Option<(Dir4, Dir4)> {
+        use Dir4::*;
         use Tile::*;
 
         match self {
@@ -86,7 +67,7 @@ impl Tile {
         }
     }
 
-    fn goes_to(&self, dir: Dir) -> Option<Dir> {
+    fn goes_to(&self, dir: Dir4) -> Option<Dir4> {
         match self.get_dirs() {
             Some((dir1, dir2)) => {
                 if dir.opp() == dir1 {
@@ -102,150 +83,24 @@ impl Tile {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Pos {
-    x: isize,
-    y: isize,
-}
-
-impl From<(isize, isize)> for Pos {
-    fn from(f: (isize, isize)) -> Pos {
-        Pos { x: f.0, y: f.1 }
-    }
-}
-
-struct Map<T> {
-    content: Vec<T>,
-    width: usize,
-    height: usize,
-}
-
-impl<T: TryFrom<char>> Map<T> {
-    fn from_str(s: &str) -> Map<T> {
-        let mut l = s.lines();
-        let width = l.next().unwrap().len();
-        let height = l.count() + 1;
-
-        let content = s
-            .replace("\r", "")
-            .chars()
-            .filter_map(|c| T::try_from(c).ok())
-            .collect();
-
-        Map {
-            content,
-            width,
-            height,
-        }
-    }
-}
-
-impl<T> Map<T> {
-    fn get_index(&self, pos: &Pos) -> Option<usize> {
-        if pos.x < self.width as isize && pos.x >= 0 && pos.y < self.height as isize && pos.y >= 0 {
-            return Some(pos.x as usize + pos.y as usize * self.width);
-        }
-        None
-    }
-
-    fn get(&self, pos: &Pos) -> Option<&T> {
-        self.content.get(self.get_index(pos)?)
-    }
-
-    fn get_mut(&mut self, pos: &Pos) -> Option<&mut T> {
-        let index = self.get_index(pos)?;
-        self.content.get_mut(index)
-    }
-}
-
-impl<T: fmt::Display> fmt::Display for Map<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        for y in 0..self.height as isize {
-            for x in 0..self.width as isize {
-                write!(f, "{}", self.get(&(x, y).into()).unwrap())?;
-            }
-            write!(f, "\n")?;
-        }
-        Ok(())
-    }
-}
-
-struct Field {
-    tiles: Vec<Tile>,
-    width: usize,
-    height: usize,
-    start: Pos,
-}
-
-impl Pos {
-    fn go(&mut self, dir: &Dir) -> &mut Pos {
-        use Dir::*;
-        match dir {
-            North => self.y -= 1,
-            East => self.x += 1,
-            West => self.x -= 1,
-            South => self.y += 1,
-        }
-
-        self
-    }
-}
-
-impl Field {
-    fn from_str(s: &str) -> Field {
-        let mut l = s.lines();
-        let width = l.next().unwrap().len();
-        let height = l.count() + 1;
-
-        let tiles: Vec<Tile> = s
-            .replace("\r", "")
-            .chars()
-            .filter_map(|c| Tile::try_from(c).ok())
-            .collect();
-
-        let idx = tiles.iter().position(|t| *t == Tile::Start).unwrap();
-        let start = Pos {
-            x: (idx % width) as isize,
-            y: (idx / width) as isize,
-        };
-
-        Field {
-            tiles,
-            width,
-            height,
-            start,
-        }
-    }
-
-    fn get(&self, pos: &Pos) -> Option<&Tile> {
-        if pos.x < self.width as isize && pos.x >= 0 && pos.y < self.height as isize && pos.y >= 0 {
-            return self.tiles.get(pos.x as usize + pos.y as usize * self.width);
-        }
-        None
-    }
+/// The start tile, the loop has to run through it.
+fn find_start(field: &Map<Tile>) -> Pos {
+    field
+        .iter()
+        .find_map(|(pos, tile)| (*tile == Tile::Start).then_some(pos))
+        .expect("No start tile")
 }
 
-impl fmt::Display for Field {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for y in 0..self.height as isize {
-            for x in 0..self.width as isize {
-                write!(f, "{}", self.get(&(x, y).into()).unwrap())?;
-            }
-            write!(f, "\n")?;
-        }
-        Ok(())
-    }
-}
-
-fn part_one(field: &Field) -> usize {
-    use Dir::*;
+fn part_one(field: &Map<Tile>) -> usize {
+    use Dir4::*;
+    let start = find_start(field);
     for s in [North, East, South, West] {
-        let mut pos = field.start.clone();
+        let mut pos = start;
         let mut dir = s;
 
         let mut n = 0;
         loop {
-            pos.go(&dir);
+            pos.go(dir);
             n += 1;
 
             dir = match field.get(&pos) {
@@ -263,26 +118,25 @@ fn part_one(field: &Field) -> usize {
     panic!("Did not find any loop");
 }
 
-fn part_two(field: &Field) -> usize {
-    use Dir::*;
+/// Walks the loop through the start, returns its tiles (ending with the start)
+/// and the two directions the start tile connects to.
+fn find_loop(field: &Map<Tile>) -> (Vec<Pos>, (Dir4, Dir4)) {
+    use Dir4::*;
     let mut loo: Vec<Pos> = Vec::new();
-    let mut start_emit = false;
 
-    'outer: for s in [North, East, South, West] {
-        let mut pos = field.start.clone();
-        let mut dir = s.clone();
+    let start = find_start(field);
+    for s in [North, East, South, West] {
+        let mut pos = start;
+        let mut dir = s;
         loo.clear();
 
         loop {
-            pos.go(&dir);
-            loo.push(pos.clone());
+            pos.go(dir);
+            loo.push(pos);
 
             dir = match field.get(&pos) {
                 Some(tile) => match tile {
-                    Tile::Start => {
-                        start_emit = s == North || dir == South;
-                        break 'outer;
-                    }
+                    Tile::Start => return (loo, (s, dir.opp())),
                     _ => match tile.goes_to(dir) {
                         Some(dir) => dir,
                         None => break,
@@ -292,16 +146,17 @@ fn part_two(field: &Field) -> usize {
             };
         }
     }
+    panic!("Did not find any loop");
+}
+
+fn part_two(field: &Map<Tile>) -> usize {
+    let (loo, start_dirs) = find_loop(field);
+    let start_emit = start_dirs.0 == Dir4::North || start_dirs.1 == Dir4::North;
 
-    let inner: Vec<bool> = vec![false; field.tiles.len()];
-    let mut m = Map {
-        content: inner,
-        width: field.width,
-        height: field.height,
-    };
+    let mut m = BitGrid::new(field.width, field.height);
 
     for pos in &loo {
-        let tile1 = field.get(&pos).unwrap();
+        let tile1 = field.get(pos).unwrap();
         if !match tile1 {
             Tile::Vertical => true,
             Tile::NorthEast => true,
@@ -313,24 +168,39 @@ fn part_two(field: &Field) -> usize {
             continue;
         }
 
-        for x in pos.x + 1..field.width as isize {
-            let new_pos = (x, pos.y).into();
-            let p = m.get_mut(&new_pos).unwrap();
-            *p = !*p;
-        }
+        m.xor_row_range(pos.y as usize, pos.x as usize + 1..field.width);
     }
 
     for pos in &loo {
-        let p = m.get_mut(&pos).unwrap();
-        *p = false;
+        m.set(pos, false);
     }
 
-    m.content.iter().filter(|&b| *b).count()
+    m.count_ones()
+}
+
+/// Same as `part_two`, but floods the outside of the loop
+/// on a map of doubled resolution, squeezing between the pipes.
+fn part_two_flood(field: &Map<Tile>) -> usize {
+    let (loo, start_dirs) = find_loop(field);
+
+    let mut pipes: Map<Option<(Dir4, Dir4)>> = Map::new(field.dims, None);
+    for pos in &loo {
+        *pipes.get_mut(pos).unwrap() = match field.get(pos).unwrap() {
+            Tile::Start => Some(start_dirs),
+            tile => tile.get_dirs(),
+        };
+    }
+
+    let outside = pipes.squeeze_fill(Option::is_some, |pipe, dir| {
+        pipe.is_some_and(|(a, b)| a == dir || b == dir)
+    });
+
+    field.width * field.height - loo.len() - outside.count_ones()
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+fn main() -> Result<(), Box<dyn Error + 'static>> {
     let input = get_input("2023", "10");
-    let field = Field::from_str(&input);
+    let field: Map<Tile> = input.parse()?;
 
     let now = time::Instant::now();
     let sol_p1 = part_one(&field);
@@ -346,6 +216,13 @@ fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
         now.elapsed().as_micros()
     );
 
+    let now = time::Instant::now();
+    let sol_p2 = part_two_flood(&field);
+    println!(
+        "Solution part two by flooding: {sol_p2} took: {}μs",
+        now.elapsed().as_micros()
+    );
+
     Ok(())
 }
 
@@ -372,8 +249,7 @@ L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L
 ";
 
-    const TEST3: &str = 
-"............
+    const TEST3: &str = "............
 .S-------7..
 .|F-----7|..
 .||.....||..
@@ -386,38 +262,44 @@ L---J.L---J.
 
     #[test]
     fn test_parse() {
-        let field = Field::from_str(TEST);
+        let field: Map<Tile> = TEST.parse().unwrap();
 
         assert_eq!(field.width, 5);
         assert_eq!(field.height, 5);
-        assert_eq!(
-            field.get(&(3isize, 1isize).into()).unwrap(),
-            &Tile::SouthWest
-        );
+        assert_eq!(field.get(&(3, 1).into()), Some(&Tile::SouthWest));
+        assert_eq!(find_start(&field), Pos::from((1, 1)));
+        assert!(".S-7.\n.|x|.\n".parse::<Map<Tile>>().is_err());
     }
 
     #[test]
     fn test_part_one() {
-        let field = Field::from_str(TEST);
-        println!("{}", field);
+        let field: Map<Tile> = TEST.parse().unwrap();
         assert_eq!(part_one(&field), 4);
     }
 
     #[test]
     fn test_part_two() {
-        let field = Field::from_str(TEST2);
+        let field: Map<Tile> = TEST2.parse().unwrap();
         assert_eq!(part_two(&field), 10);
     }
 
+    #[test]
+    fn test_part_two_flood() {
+        for (test, inner) in [(TEST, 1), (TEST2, 10), (TEST3, 6)] {
+            let field: Map<Tile> = test.parse().unwrap();
+            assert_eq!(part_two_flood(&field), inner);
+        }
+    }
+
     #[test]
     fn test_part_two_easy() {
-        let field = Field::from_str(TEST);
+        let field: Map<Tile> = TEST.parse().unwrap();
         assert_eq!(part_two(&field), 1);
     }
 
     #[test]
     fn test_part_two_medium() {
-        let field = Field::from_str(TEST3);
+        let field: Map<Tile> = TEST3.parse().unwrap();
         assert_eq!(part_two(&field), 6);
     }
 }
@@ -1,19 +1,17 @@
 use rustvent2023::{get_input, Pos};
-use std::convert::{From, Into, TryFrom};
-use std::fmt;
 use std::time;
 
+/// The galaxies in reading order.
 fn parse(input: &str) -> Vec<Pos> {
     input
         .lines()
         .enumerate()
-        .map(|l| {
-            l.1.chars()
+        .flat_map(|(y, line)| {
+            line.chars()
                 .enumerate()
-                .filter(|c| c.1 == '#')
-                .map(move |c| (c.0 as isize, l.0 as isize).into())
+                .filter(|&(_, c)| c == '#')
+                .map(move |(x, _)| Pos::from((x as isize, y as isize)))
         })
-        .flatten()
         .collect()
 }
 
@@ -33,7 +31,7 @@ fn part_n(gals: &Vec<Pos>, expand: isize) -> usize {
     for x in expand_x {
         for gal in &mut expanded {
             if gal.x > x {
-                gal.x += expand-1;
+                gal.x += expand - 1;
             }
         }
     }
@@ -41,7 +39,7 @@ fn part_n(gals: &Vec<Pos>, expand: isize) -> usize {
     for y in expand_y {
         for gal in &mut expanded {
             if gal.y > y {
-                gal.y += expand-1;
+                gal.y += expand - 1;
             }
         }
     }
@@ -50,7 +48,7 @@ fn part_n(gals: &Vec<Pos>, expand: isize) -> usize {
 
     for i in 0..expanded.len() - 1 {
         for j in i + 1..expanded.len() {
-            dists.push((expanded[i].x - expanded[j].x).abs() as usize + (expanded[i].y - expanded[j].y).abs() as usize);
+            dists.push(expanded[i].manhattan(&expanded[j]));
         }
     }
 
@@ -97,8 +95,9 @@ mod tests {
     #[test]
     fn test_parse() {
         let gals = parse(TEST);
-        println!("{:?}", gals);
-        assert!(gals.contains(&(3, 0).into()));
+        assert_eq!(gals.len(), 9);
+        assert_eq!(gals[0], (3, 0).into());
+        assert_eq!(gals[8], (4, 9).into());
     }
 
     #[test]
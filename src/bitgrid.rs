@@ -0,0 +1,339 @@
+use crate::{Map, Pos};
+use std::fmt;
+use std::ops::{BitAndAssign, BitOrAssign, BitXorAssign, Range};
+
+/// Grid of booleans packed into 64 cells per word.
+/// Every row starts at a new word, cell `x` of a row is bit `x % 64`
+/// of word `x / 64`. Bits beyond `width` are always zero.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    words_per_row: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Mask of the bits of word `w` that lie inside `range`.
+fn word_mask(w: usize, range: &Range<usize>) -> u64 {
+    let lo = range.start.saturating_sub(w * 64).min(64);
+    let hi = range.end.saturating_sub(w * 64).min(64);
+    if lo >= hi {
+        return 0;
+    }
+    (u64::MAX >> (64 - (hi - lo))) << lo
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        let words_per_row = width.div_ceil(64);
+        BitGrid {
+            words: vec![0; words_per_row * height],
+            words_per_row,
+            width,
+            height,
+        }
+    }
+
+    /// Sets every cell of `map` for which `pred` holds.
+    pub fn from_map<T, F: Fn(&T) -> bool>(map: &Map<T>, pred: F) -> BitGrid {
+        let mut grid = BitGrid::new(map.width, map.height);
+        for (i, el) in map.content.iter().enumerate() {
+            if pred(el) {
+                grid.words[i / map.width * grid.words_per_row + i % map.width / 64] |=
+                    1 << (i % map.width % 64);
+            }
+        }
+        grid
+    }
+
+    /// Converts into a map with `on` for set and `off` for unset cells.
+    pub fn to_map<T: Clone>(&self, on: T, off: T) -> Map<T> {
+        let content = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                if self.get_bit(x, y) {
+                    on.clone()
+                } else {
+                    off.clone()
+                }
+            })
+            .collect();
+
+        Map {
+            content,
+            dims: [self.width, self.height],
+        }
+    }
+
+    fn index(&self, pos: &Pos) -> Option<(usize, u64)> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width as isize || pos.y >= self.height as isize {
+            return None;
+        }
+        let (x, y) = (pos.x as usize, pos.y as usize);
+        Some((y * self.words_per_row + x / 64, 1 << (x % 64)))
+    }
+
+    fn get_bit(&self, x: usize, y: usize) -> bool {
+        self.words[y * self.words_per_row + x / 64] & (1 << (x % 64)) != 0
+    }
+
+    pub fn get(&self, pos: &Pos) -> Option<bool> {
+        let (i, bit) = self.index(pos)?;
+        Some(self.words[i] & bit != 0)
+    }
+
+    /// # Panics
+    /// If `pos` is outside of the grid.
+    pub fn set(&mut self, pos: &Pos, value: bool) {
+        let (i, bit) = self.index(pos).expect("Position outside of grid");
+        if value {
+            self.words[i] |= bit;
+        } else {
+            self.words[i] &= !bit;
+        }
+    }
+
+    /// # Panics
+    /// If `pos` is outside of the grid.
+    pub fn toggle(&mut self, pos: &Pos) {
+        let (i, bit) = self.index(pos).expect("Position outside of grid");
+        self.words[i] ^= bit;
+    }
+
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Toggles the cells `xs` of row `y`.
+    pub fn xor_row_range(&mut self, y: usize, xs: Range<usize>) {
+        let xs = xs.start..xs.end.min(self.width);
+        for (w, word) in self.row_mut(y).iter_mut().enumerate() {
+            *word ^= word_mask(w, &xs);
+        }
+    }
+
+    /// Sets the cells `xs` of row `y`.
+    pub fn or_row_range(&mut self, y: usize, xs: Range<usize>) {
+        let xs = xs.start..xs.end.min(self.width);
+        for (w, word) in self.row_mut(y).iter_mut().enumerate() {
+            *word |= word_mask(w, &xs);
+        }
+    }
+
+    /// Clears all cells of row `y` except `xs`.
+    pub fn and_row_range(&mut self, y: usize, xs: Range<usize>) {
+        for (w, word) in self.row_mut(y).iter_mut().enumerate() {
+            *word &= word_mask(w, &xs);
+        }
+    }
+
+    /// Combines row `y` with the words of `other`, e.g. a row of another grid of the same width.
+    pub fn xor_row(&mut self, y: usize, other: &[u64]) {
+        self.row_mut(y)
+            .iter_mut()
+            .zip(other)
+            .for_each(|(a, b)| *a ^= b);
+        self.clear_padding(y);
+    }
+
+    pub fn or_row(&mut self, y: usize, other: &[u64]) {
+        self.row_mut(y)
+            .iter_mut()
+            .zip(other)
+            .for_each(|(a, b)| *a |= b);
+        self.clear_padding(y);
+    }
+
+    pub fn and_row(&mut self, y: usize, other: &[u64]) {
+        self.row_mut(y)
+            .iter_mut()
+            .zip(other)
+            .for_each(|(a, b)| *a &= b);
+    }
+
+    /// Moves every cell of row `y` by `n` towards larger `x`.
+    /// Cells moved past the edge are lost, free cells are unset.
+    pub fn shift_row_right(&mut self, y: usize, n: usize) {
+        let (words, bits) = (n / 64, n % 64);
+        let row = self.row_mut(y);
+        for i in (0..row.len()).rev() {
+            let lo = i.checked_sub(words).map_or(0, |j| row[j]);
+            let carry = match i.checked_sub(words + 1) {
+                Some(j) if bits > 0 => row[j] >> (64 - bits),
+                _ => 0,
+            };
+            row[i] = (lo << bits) | carry;
+        }
+        self.clear_padding(y);
+    }
+
+    /// Moves every cell of row `y` by `n` towards smaller `x`.
+    /// Cells moved past the edge are lost, free cells are unset.
+    pub fn shift_row_left(&mut self, y: usize, n: usize) {
+        let (words, bits) = (n / 64, n % 64);
+        let row = self.row_mut(y);
+        let len = row.len();
+        for i in 0..len {
+            let hi = row.get(i + words).copied().unwrap_or(0);
+            let carry = match row.get(i + words + 1) {
+                Some(w) if bits > 0 => w << (64 - bits),
+                _ => 0,
+            };
+            row[i] = (hi >> bits) | carry;
+        }
+    }
+
+    fn clear_padding(&mut self, y: usize) {
+        let width = self.width;
+        if let Some(last) = self.row_mut(y).last_mut() {
+            *last &= word_mask(width.saturating_sub(1) / 64, &(0..width));
+        }
+    }
+
+    pub fn count_row(&self, y: usize) -> usize {
+        self.row(y).iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+impl BitXorAssign<&BitGrid> for BitGrid {
+    fn bitxor_assign(&mut self, rhs: &BitGrid) {
+        assert_eq!((self.width, self.height), (rhs.width, rhs.height));
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a ^= b);
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        assert_eq!((self.width, self.height), (rhs.width, rhs.height));
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        assert_eq!((self.width, self.height), (rhs.width, rhs.height));
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(a, b)| *a &= b);
+    }
+}
+
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                write!(f, "{}", if self.get_bit(x, y) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const TEST: &str = "#..#.
+.##..
+....#
+";
+
+    fn grid() -> BitGrid {
+        let map: Map<char> = Map::from_str(TEST).unwrap();
+        BitGrid::from_map(&map, |&c| c == '#')
+    }
+
+    #[test]
+    fn test_map_conversion() {
+        let grid = grid();
+        assert_eq!(grid.count_ones(), 5);
+        assert_eq!(grid.get(&(3, 0).into()), Some(true));
+        assert_eq!(grid.get(&(0, 1).into()), Some(false));
+        assert_eq!(grid.get(&(5, 0).into()), None);
+        assert_eq!(grid.to_string(), TEST);
+        assert_eq!(grid.to_map('#', '.').to_string(), TEST);
+    }
+
+    #[test]
+    fn test_set_toggle() {
+        let mut grid = grid();
+        grid.set(&(0, 0).into(), false);
+        grid.set(&(4, 1).into(), true);
+        grid.toggle(&(2, 2).into());
+        assert_eq!(grid.to_string(), "...#.\n.##.#\n..#.#\n");
+        assert_eq!(grid.count_row(1), 3);
+    }
+
+    #[test]
+    fn test_row_ranges() {
+        let mut grid = grid();
+        grid.xor_row_range(0, 1..10);
+        grid.or_row_range(1, 3..4);
+        grid.and_row_range(2, 0..2);
+        assert_eq!(grid.to_string(), "###.#\n.###.\n.....\n");
+
+        let mut other = grid.clone();
+        other.xor_row(0, grid.row(1));
+        assert_eq!(other.to_string(), "#..##\n.###.\n.....\n");
+        other.or_row(2, &[u64::MAX]);
+        other.and_row(1, grid.row(0));
+        assert_eq!(other.to_string(), "#..##\n.##..\n#####\n");
+    }
+
+    #[test]
+    fn test_shift() {
+        let mut grid = grid();
+        grid.shift_row_right(0, 1);
+        grid.shift_row_left(1, 1);
+        grid.shift_row_right(2, 3);
+        assert_eq!(grid.to_string(), ".#..#\n##...\n.....\n");
+    }
+
+    #[test]
+    fn test_wide_rows() {
+        let mut grid = BitGrid::new(150, 2);
+        grid.or_row_range(0, 60..70);
+        assert_eq!(grid.count_row(0), 10);
+
+        grid.shift_row_right(0, 75);
+        assert_eq!(grid.count_row(0), 10);
+        assert_eq!(grid.get(&(135, 0).into()), Some(true));
+        assert_eq!(grid.get(&(144, 0).into()), Some(true));
+        assert_eq!(grid.get(&(145, 0).into()), Some(false));
+
+        grid.shift_row_right(0, 10);
+        assert_eq!(grid.count_row(0), 5);
+
+        grid.shift_row_left(0, 100);
+        assert_eq!(grid.count_row(0), 5);
+        assert_eq!(grid.get(&(45, 0).into()), Some(true));
+        assert_eq!(grid.get(&(49, 0).into()), Some(true));
+
+        let mut full = BitGrid::new(150, 2);
+        full.xor_row_range(1, 0..1000);
+        assert_eq!(full.count_ones(), 150);
+        full ^= &grid;
+        assert_eq!(full.count_ones(), 155);
+        full &= &grid;
+        assert_eq!(full.count_ones(), 5);
+        full |= &grid;
+        assert_eq!(full, grid);
+    }
+}
@@ -0,0 +1,674 @@
+use std::error::Error;
+use std::fmt;
+
+/// A card, the suit is only known for decks that have them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Card {
+    pub rank: char,
+    pub suit: Option<char>,
+}
+
+impl From<char> for Card {
+    fn from(rank: char) -> Card {
+        Card { rank, suit: None }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.rank)?;
+        if let Some(suit) = self.suit {
+            write!(f, "{suit}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Kinds of hands, listed from weakest to strongest in poker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    /// Five cards of consecutive ranks, the highest rank may also precede the lowest.
+    Straight,
+    /// Five cards of the same suit.
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    FiveOfAKind,
+}
+
+/// What categories look at in a hand.
+struct Shape {
+    /// Sizes of the groups of equal ranks, largest first.
+    counts: Vec<usize>,
+    straight: bool,
+    flush: bool,
+}
+
+impl Category {
+    fn fits(self, shape: &Shape) -> bool {
+        let count = |i: usize| shape.counts.get(i).copied().unwrap_or(0);
+        match self {
+            Category::HighCard => true,
+            Category::OnePair => count(0) >= 2,
+            Category::TwoPair => count(0) >= 2 && count(1) >= 2,
+            Category::ThreeOfAKind => count(0) >= 3,
+            Category::Straight => shape.straight,
+            Category::Flush => shape.flush,
+            Category::FullHouse => count(0) >= 3 && count(1) >= 2,
+            Category::FourOfAKind => count(0) >= 4,
+            Category::StraightFlush => shape.straight && shape.flush,
+            Category::FiveOfAKind => count(0) >= 5,
+        }
+    }
+}
+
+/// How hands of the same category are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Card by card in the order they were dealt, like camel cards.
+    DealtOrder,
+    /// Larger groups first, then higher ranks first, like poker.
+    /// Straights only compare their highest card.
+    Groups,
+}
+
+/// Sort key of a hand, comparing these compares the hands.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Strength {
+    /// Index of the category in [`Rules::categories`],
+    /// counted from the end if low hands win.
+    pub level: usize,
+    /// Card ranks in tie break order, likewise counted from the end if low hands win.
+    pub tie_break: Vec<usize>,
+}
+
+/// Configuration of a card game: which cards exist and how hands are ranked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// Card labels from lowest to highest rank.
+    ranks: Vec<char>,
+    /// Categories from weakest to strongest, a hand is the strongest one it fits.
+    categories: Vec<Category>,
+    tie_break: TieBreak,
+    /// Cards that may stand for any card when categorizing.
+    wildcards: Vec<char>,
+    /// Whether the weakest hand wins, like in lowball.
+    low_wins: bool,
+}
+
+impl Rules {
+    /// # Panics
+    /// If `ranks` has duplicate labels or `categories` is empty.
+    pub fn new(ranks: &str, categories: &[Category], tie_break: TieBreak) -> Rules {
+        let ranks: Vec<char> = ranks.chars().collect();
+        assert!(
+            ranks
+                .iter()
+                .enumerate()
+                .all(|(i, c)| !ranks[..i].contains(c)),
+            "Duplicate card in {ranks:?}"
+        );
+        assert!(!categories.is_empty(), "No hand categories");
+        Rules {
+            ranks,
+            categories: categories.to_vec(),
+            tie_break,
+            wildcards: Vec::new(),
+            low_wins: false,
+        }
+    }
+
+    /// Day 7's camel cards.
+    pub fn camel_cards() -> Rules {
+        use Category::*;
+        Rules::new(
+            "23456789TJQKA",
+            &[
+                HighCard,
+                OnePair,
+                TwoPair,
+                ThreeOfAKind,
+                FullHouse,
+                FourOfAKind,
+                FiveOfAKind,
+            ],
+            TieBreak::DealtOrder,
+        )
+    }
+
+    /// Five card poker, cards are written like `TH` for the ten of hearts.
+    /// Five of a kind is only possible with wildcards.
+    pub fn poker() -> Rules {
+        use Category::*;
+        Rules::new(
+            "23456789TJQKA",
+            &[
+                HighCard,
+                OnePair,
+                TwoPair,
+                ThreeOfAKind,
+                Straight,
+                Flush,
+                FullHouse,
+                FourOfAKind,
+                StraightFlush,
+                FiveOfAKind,
+            ],
+            TieBreak::Groups,
+        )
+    }
+
+    /// Ace to five lowball: aces are low, straights and flushes don't count
+    /// and the weakest hand wins, so `5432A` is the best hand.
+    pub fn ace_to_five_lowball() -> Rules {
+        use Category::*;
+        Rules::new(
+            "A23456789TJQK",
+            &[
+                HighCard,
+                OnePair,
+                TwoPair,
+                ThreeOfAKind,
+                FullHouse,
+                FourOfAKind,
+            ],
+            TieBreak::Groups,
+        )
+        .lowball()
+    }
+
+    /// The same rules with a different order of the cards, lowest first.
+    /// Fails on duplicate labels or if a wildcard is not one of the new ranks.
+    pub fn with_ranks(&self, ranks: &str) -> Result<Rules, Box<dyn Error + 'static>> {
+        let labels: Vec<char> = ranks.chars().collect();
+        if let Some(c) = labels
+            .iter()
+            .enumerate()
+            .find_map(|(i, c)| labels[..i].contains(c).then_some(c))
+        {
+            return Err(format!("Duplicate card {c:?} in {ranks:?}").into());
+        }
+        if let Some(w) = self.wildcards.iter().find(|w| !labels.contains(w)) {
+            return Err(format!("Wildcard {w:?} is not in {ranks:?}").into());
+        }
+        Ok(Rules {
+            wildcards: self.wildcards.clone(),
+            low_wins: self.low_wins,
+            ..Rules::new(ranks, &self.categories, self.tie_break)
+        })
+    }
+
+    /// The same rules where `wildcards` may stand for any card when categorizing.
+    /// For tie breaks they keep their own rank, so use [`Rules::with_ranks`] to move them.
+    ///
+    /// # Panics
+    /// If a wildcard is not one of the ranks.
+    pub fn with_wildcards(&self, wildcards: &str) -> Rules {
+        let wildcards: Vec<char> = wildcards.chars().collect();
+        for &w in &wildcards {
+            assert!(self.rank(w).is_some(), "Wildcard {w:?} is no card");
+        }
+        Rules {
+            wildcards,
+            ..self.clone()
+        }
+    }
+
+    /// The same rules where the weakest hand wins.
+    pub fn lowball(&self) -> Rules {
+        Rules {
+            low_wins: !self.low_wins,
+            ..self.clone()
+        }
+    }
+
+    pub fn wildcards(&self) -> &[char] {
+        &self.wildcards
+    }
+
+    pub fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    /// Card labels from lowest to highest.
+    pub fn ranks(&self) -> &[char] {
+        &self.ranks
+    }
+
+    pub fn rank(&self, card: char) -> Option<usize> {
+        self.ranks.iter().position(|&c| c == card)
+    }
+
+    /// Reads a hand like `KTJJT`, or with suits separated by whitespace like `KH TD 5C`,
+    /// rejecting unknown cards.
+    pub fn parse_cards(&self, hand: &str) -> Result<Vec<Card>, Box<dyn Error + 'static>> {
+        let cards: Vec<Card> = if hand.contains(char::is_whitespace) {
+            hand.split_whitespace()
+                .map(|token| {
+                    let mut chars = token.chars();
+                    match (chars.next(), chars.next(), chars.next()) {
+                        (Some(rank), Some(suit), None) => Ok(Card {
+                            rank,
+                            suit: Some(suit),
+                        }),
+                        _ => Err(format!("Invalid card {token:?} in {hand:?}")),
+                    }
+                })
+                .collect::<Result<_, _>>()?
+        } else {
+            hand.chars().map(Card::from).collect()
+        };
+
+        match cards.iter().find(|c| self.rank(c.rank).is_none()) {
+            Some(c) => Err(format!("Unknown card {c} in {hand:?}").into()),
+            None => Ok(cards),
+        }
+    }
+
+    /// Groups of equal ranks as `(count, rank)`, larger groups and higher ranks first.
+    fn groups(&self, cards: &[Card]) -> Vec<(usize, usize)> {
+        let mut counts = vec![0; self.ranks.len()];
+        for c in cards {
+            counts[self.rank(c.rank).unwrap()] += 1;
+        }
+        let mut groups: Vec<(usize, usize)> = counts
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, n)| n > 0)
+            .map(|(rank, n)| (n, rank))
+            .collect();
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+        groups
+    }
+
+    fn is_wild(&self, card: &Card) -> bool {
+        self.wildcards.contains(&card.rank)
+    }
+
+    /// Highest rank of the best straight five cards can make,
+    /// where the wildcards fill the gaps.
+    fn straight_top(&self, cards: &[Card]) -> Option<usize> {
+        if cards.len() != 5 || self.ranks.len() < 5 {
+            return None;
+        }
+        let n = self.ranks.len();
+        let regular: Vec<usize> = cards
+            .iter()
+            .filter(|c| !self.is_wild(c))
+            .map(|c| self.rank(c.rank).unwrap())
+            .collect();
+
+        // windows of ranks by their highest rank, the lowest one starts with the highest rank
+        let mut windows: Vec<(usize, Vec<usize>)> =
+            (4..n).map(|top| (top, (top - 4..=top).collect())).collect();
+        windows.push((3, vec![n - 1, 0, 1, 2, 3]));
+
+        windows
+            .into_iter()
+            .filter(|(_, window)| {
+                regular.iter().all(|r| window.contains(r))
+                    && regular
+                        .iter()
+                        .enumerate()
+                        .all(|(i, r)| !regular[..i].contains(r))
+            })
+            .map(|(top, _)| top)
+            .max()
+    }
+
+    /// Whether five cards can all have the same suit, with the wildcards matching.
+    fn is_flush(&self, cards: &[Card]) -> bool {
+        let mut suits = cards.iter().filter(|c| !self.is_wild(c)).map(|c| c.suit);
+        match suits.next() {
+            Some(Some(first)) => cards.len() == 5 && suits.all(|s| s == Some(first)),
+            Some(None) => false,
+            None => cards.len() == 5 && cards.iter().all(|c| c.suit.is_some()),
+        }
+    }
+
+    fn best_level(&self, shape: &Shape) -> usize {
+        self.categories
+            .iter()
+            .rposition(|c| c.fits(shape))
+            .unwrap_or(0)
+    }
+
+    /// Index in [`Rules::categories`] of the strongest category the cards fit,
+    /// with the wildcards replaced by the cards that make it strongest.
+    ///
+    /// # Panics
+    /// If a card is not one of the ranks.
+    pub fn level(&self, cards: &[Card]) -> usize {
+        let (wild, regular): (Vec<Card>, Vec<Card>) = cards.iter().partition(|c| self.is_wild(c));
+        let counts: Vec<usize> = self.groups(&regular).iter().map(|&(n, _)| n).collect();
+
+        // the groups only matter for the two largest ones,
+        // so try every way to split the wildcards between them
+        let count = |i: usize| counts.get(i).copied().unwrap_or(0);
+        let by_groups = (0..=wild.len())
+            .map(|a| {
+                let mut split = vec![count(0) + a, count(1) + wild.len() - a];
+                split.extend(counts.iter().skip(2));
+                split.sort_unstable_by(|a, b| b.cmp(a));
+                self.best_level(&Shape {
+                    counts: split,
+                    straight: false,
+                    flush: false,
+                })
+            })
+            .max()
+            .unwrap();
+
+        // straights and flushes need all cards distinct resp. alike
+        let uses = |c: Category| self.categories.contains(&c);
+        let by_sequence = self.best_level(&Shape {
+            counts: Vec::new(),
+            straight: (uses(Category::Straight) || uses(Category::StraightFlush))
+                && self.straight_top(cards).is_some(),
+            flush: (uses(Category::Flush) || uses(Category::StraightFlush)) && self.is_flush(cards),
+        });
+
+        by_groups.max(by_sequence)
+    }
+
+    pub fn category(&self, cards: &[Card]) -> Category {
+        self.categories[self.level(cards)]
+    }
+
+    /// # Panics
+    /// If a card is not one of the ranks.
+    pub fn strength(&self, cards: &[Card]) -> Strength {
+        let level = self.level(cards);
+        let tie_break = match (self.tie_break, self.categories[level]) {
+            (TieBreak::DealtOrder, _) => cards.iter().map(|c| self.rank(c.rank).unwrap()).collect(),
+            (TieBreak::Groups, Category::Straight | Category::StraightFlush) => {
+                vec![self.straight_top(cards).unwrap()]
+            }
+            (TieBreak::Groups, _) => self.groups(cards).iter().map(|&(_, r)| r).collect(),
+        };
+
+        if self.low_wins {
+            Strength {
+                level: self.categories.len() - 1 - level,
+                tie_break: tie_break
+                    .into_iter()
+                    .map(|r| self.ranks.len() - 1 - r)
+                    .collect(),
+            }
+        } else {
+            Strength { level, tie_break }
+        }
+    }
+
+    /// The strongest hand of `size` cards among `cards`, like the best five of seven.
+    ///
+    /// # Panics
+    /// If there are fewer than `size` or more than 20 cards.
+    pub fn best_of(&self, cards: &[Card], size: usize) -> (Vec<Card>, Strength) {
+        assert!(
+            size <= cards.len() && cards.len() <= 20,
+            "Can't pick {size} of {cards:?}"
+        );
+        (0u32..1 << cards.len())
+            .filter(|mask| mask.count_ones() as usize == size)
+            .map(|mask| {
+                let hand: Vec<Card> = (0..cards.len())
+                    .filter(|i| mask & 1 << i != 0)
+                    .map(|i| cards[i])
+                    .collect();
+                let strength = self.strength(&hand);
+                (hand, strength)
+            })
+            .max_by(|a, b| a.1.cmp(&b.1))
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lcg;
+    use std::cmp::Ordering;
+
+    fn cards(s: &str) -> Vec<Card> {
+        s.chars().map(Card::from).collect()
+    }
+
+    #[test]
+    fn test_categories() {
+        use Category::*;
+        let rules = Rules::camel_cards();
+        for (hand, category) in [
+            ("AAAAA", FiveOfAKind),
+            ("AA8AA", FourOfAKind),
+            ("23332", FullHouse),
+            ("TTT98", ThreeOfAKind),
+            ("23432", TwoPair),
+            ("A23A4", OnePair),
+            ("23456", HighCard),
+        ] {
+            assert_eq!(rules.category(&cards(hand)), category, "{hand}");
+        }
+
+        // without five of a kind, it is just four of a kind
+        let fewer = Rules::new(
+            "23456789TJQKA",
+            &[HighCard, OnePair, FourOfAKind],
+            TieBreak::DealtOrder,
+        );
+        assert_eq!(fewer.category(&cards("AAAAA")), FourOfAKind);
+        assert_eq!(fewer.category(&cards("23332")), OnePair);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let rules = Rules::camel_cards();
+        let cmp = |a: &str, b: &str| rules.strength(&cards(a)).cmp(&rules.strength(&cards(b)));
+
+        assert_eq!(cmp("33332", "2AAAA"), Ordering::Greater);
+        assert_eq!(cmp("77888", "77788"), Ordering::Greater);
+        assert_eq!(cmp("KK677", "KTJJT"), Ordering::Greater);
+        // identical hands are simply equal
+        assert_eq!(cmp("QQQJA", "QQQJA"), Ordering::Equal);
+
+        let jokers_low = rules.with_ranks("J23456789TQKA").unwrap();
+        assert!(jokers_low.strength(&cards("JKKK2")) < jokers_low.strength(&cards("QQQQ2")));
+        assert!(rules.strength(&cards("JKKK2")) < rules.strength(&cards("QQQQ2")));
+        assert!(jokers_low.strength(&cards("J2345")) < jokers_low.strength(&cards("23456")));
+        assert!(rules.strength(&cards("J2345")) > rules.strength(&cards("23456")));
+
+        let poker_like = Rules::new("23456789TJQKA", rules.categories(), TieBreak::Groups);
+        assert!(poker_like.strength(&cards("A2233")) < poker_like.strength(&cards("24433")));
+        assert!(rules.strength(&cards("A2233")) > rules.strength(&cards("24433")));
+    }
+
+    /// The strongest level of all ways to replace the wildcards by any cards.
+    fn enumerated_level(rules: &Rules, cards: &[Card]) -> usize {
+        let mut combinations = vec![cards.to_vec()];
+        for (i, c) in cards.iter().enumerate() {
+            if !rules.wildcards().contains(&c.rank) {
+                continue;
+            }
+            let suits: Vec<Option<char>> = match c.suit {
+                Some(_) => "CDHS".chars().map(Some).collect(),
+                None => vec![None],
+            };
+            combinations = combinations
+                .into_iter()
+                .flat_map(|comb| {
+                    let suits = suits.clone();
+                    rules.ranks().iter().flat_map(move |&rank| {
+                        let comb = comb.clone();
+                        suits.clone().into_iter().map(move |suit| {
+                            let mut comb = comb.clone();
+                            comb[i] = Card { rank, suit };
+                            comb
+                        })
+                    })
+                })
+                .collect();
+        }
+        let plain = Rules::new(
+            &rules.ranks().iter().collect::<String>(),
+            rules.categories(),
+            TieBreak::DealtOrder,
+        );
+        combinations.iter().map(|c| plain.level(c)).max().unwrap()
+    }
+
+    #[test]
+    fn test_wildcards() {
+        use Category::*;
+        let rules = Rules::camel_cards()
+            .with_ranks("J23456789TQKA")
+            .unwrap()
+            .with_wildcards("J");
+        assert_eq!(rules.category(&cards("T55J5")), FourOfAKind);
+        assert_eq!(rules.category(&cards("KTJJT")), FourOfAKind);
+        assert_eq!(rules.category(&cards("JJJJJ")), FiveOfAKind);
+        assert_eq!(rules.category(&cards("2345J")), OnePair);
+        assert_eq!(rules.category(&cards("2233J")), FullHouse);
+        // the joker still ranks lowest
+        assert!(rules.strength(&cards("JKKK2")) < rules.strength(&cards("QQQQ2")));
+        // reordering must keep the wildcards
+        assert!(rules.with_ranks("23456789TQKA").is_err());
+        assert!(rules.with_ranks("JJ23456789TQKA").is_err());
+        assert!(rules.with_ranks("23456789TQKAJ").is_ok());
+
+        let mut rng = Lcg(2023);
+        let two_wilds = Rules::camel_cards().with_wildcards("J2");
+        let fewer = Rules::new(
+            "23456789TJQKA",
+            &[HighCard, TwoPair, FullHouse],
+            TieBreak::DealtOrder,
+        )
+        .with_wildcards("JQ");
+        for rules in [&rules, &two_wilds, &fewer] {
+            for _ in 0..100 {
+                // few distinct cards, so that there are many groups and wildcards
+                let hand: Vec<Card> = (0..5)
+                    .map(|_| rules.ranks()[[0, 1, 9, 10, 12][rng.next(5) as usize]].into())
+                    .collect();
+                assert_eq!(
+                    rules.level(&hand),
+                    enumerated_level(rules, &hand),
+                    "{hand:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_poker_wildcards() {
+        let rules = Rules::poker().with_wildcards("2");
+        let mut rng = Lcg(7);
+        for _ in 0..300 {
+            // at most two wildcards, and close ranks and few suits for straights and flushes
+            let mut hand: Vec<Card> = (0..5)
+                .map(|_| Card {
+                    rank: rules.ranks()[[1, 2, 3, 4, 5, 12][rng.next(6) as usize]],
+                    suit: Some(['H', 'S'][rng.next(2) as usize]),
+                })
+                .collect();
+            for c in hand.iter_mut().skip(rng.next(3) as usize + 3) {
+                c.rank = '2';
+            }
+            assert_eq!(
+                rules.level(&hand),
+                enumerated_level(&rules, &hand),
+                "{hand:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_poker() {
+        use Category::*;
+        let rules = Rules::poker();
+        let hand = |s: &str| rules.parse_cards(s).unwrap();
+        for (cards, category) in [
+            ("TH JH QH KH AH", StraightFlush),
+            ("5D 4D 3D 2D AD", StraightFlush),
+            ("9C 9D 9H 9S 2C", FourOfAKind),
+            ("3C 3D 3H 2S 2C", FullHouse),
+            ("2H 7H 9H JH KH", Flush),
+            ("6C 5D 4H 3S 2C", Straight),
+            ("AC 2D 3H 4S 5C", Straight),
+            ("QC KD AH 2S 3C", HighCard),
+            ("7C 7D 7H KS 2C", ThreeOfAKind),
+            ("7C 7D KH KS 2C", TwoPair),
+            ("7C 7D QH KS 2C", OnePair),
+            ("7C 8D QH KS 2C", HighCard),
+        ] {
+            assert_eq!(rules.category(&hand(cards)), category, "{cards}");
+        }
+
+        let cmp = |a: &str, b: &str| rules.strength(&hand(a)).cmp(&rules.strength(&hand(b)));
+        // the wheel is the lowest straight
+        assert_eq!(cmp("AC 2D 3H 4S 5C", "6C 2D 3H 4S 5C"), Ordering::Less);
+        assert_eq!(cmp("TC JD QH KS AC", "9C TD JH QS KC"), Ordering::Greater);
+        assert_eq!(cmp("2H 7H 9H JH KH", "TC JD QH KS AC"), Ordering::Greater);
+        // full houses by their three of a kind first
+        assert_eq!(cmp("3C 3D 3H AS AC", "2C 2D 2H KS KC"), Ordering::Greater);
+        // two pairs by the higher pair, then the lower pair, then the kicker
+        assert_eq!(cmp("KC KD 2H 2S AC", "KH KS 3H 3S 4C"), Ordering::Less);
+        assert_eq!(cmp("KC KD 3C 3D AC", "KH KS 3H 3S 4C"), Ordering::Greater);
+        // suits never break ties
+        assert_eq!(cmp("KC KD 3C 3D AC", "KH KS 3H 3S AD"), Ordering::Equal);
+
+        assert!(rules.parse_cards("TH JH 1H").is_err());
+        assert!(rules.parse_cards("TH JHX").is_err());
+        assert_eq!(hand("TH JH")[0].to_string(), "TH");
+    }
+
+    #[test]
+    fn test_best_of_seven() {
+        let rules = Rules::poker();
+        let hand = |s: &str| rules.parse_cards(s).unwrap();
+
+        let (best, strength) = rules.best_of(&hand("2C 3H 4D 5S 9H KH AH"), 5);
+        assert_eq!(rules.categories()[strength.level], Category::Straight);
+        assert_eq!(strength.tie_break, vec![rules.rank('5').unwrap()]);
+        assert_eq!(best.len(), 5);
+
+        let (best, _) = rules.best_of(&hand("2H 3H 4D 5S 9H KH AH"), 5);
+        assert_eq!(best, hand("2H 3H 9H KH AH"));
+
+        // the best full house of two three of a kinds
+        let (best, strength) = rules.best_of(&hand("QC QD QH 7S 7C 7D 2H"), 5);
+        assert_eq!(rules.categories()[strength.level], Category::FullHouse);
+        assert_eq!(best.iter().filter(|c| c.rank == 'Q').count(), 3);
+    }
+
+    #[test]
+    fn test_lowball() {
+        let rules = Rules::ace_to_five_lowball();
+        let cmp = |a: &str, b: &str| rules.strength(&cards(a)).cmp(&rules.strength(&cards(b)));
+
+        // the wheel is the best hand, straights and flushes don't count
+        assert_eq!(cmp("5432A", "6432A"), Ordering::Greater);
+        assert_eq!(cmp("75432", "76432"), Ordering::Greater);
+        assert_eq!(cmp("KQJT9", "22345"), Ordering::Greater);
+        assert_eq!(cmp("AA234", "AA235"), Ordering::Greater);
+
+        // high hands win again with a second flip
+        let high = rules.lowball();
+        assert_eq!(
+            high.strength(&cards("KQJT9"))
+                .cmp(&high.strength(&cards("22345"))),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_parse_cards() {
+        let rules = Rules::camel_cards();
+        assert_eq!(rules.parse_cards("KTJJT").unwrap(), cards("KTJJT"));
+        assert!(rules.parse_cards("KT1JT").is_err());
+    }
+}
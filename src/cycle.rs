@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The states `start..start + len` repeat forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The step in `0..start + len` with the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+
+    /// Replays the steps before the cycle and one round of it,
+    /// collecting every state for which `is_target` holds.
+    pub fn orbit<S, F, P>(&self, start: S, next: F, is_target: P) -> Orbit<S>
+    where
+        F: Fn(&S) -> S,
+        P: Fn(&S) -> bool,
+    {
+        let mut hits = Vec::new();
+        let mut state = start;
+        for n in 0..self.start + self.len {
+            let following = next(&state);
+            if is_target(&state) {
+                hits.push((n, state));
+            }
+            state = following;
+        }
+        Orbit { cycle: *self, hits }
+    }
+}
+
+/// A [`Cycle`] together with the steps whose states hit a target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orbit<S> {
+    pub cycle: Cycle,
+    /// Hits in `0..cycle.start + cycle.len`, sorted by step.
+    pub hits: Vec<(usize, S)>,
+}
+
+impl<S> Orbit<S> {
+    pub fn is_hit(&self, n: usize) -> bool {
+        let n = self.cycle.reduce(n);
+        self.hits.binary_search_by_key(&n, |(m, _)| *m).is_ok()
+    }
+
+    /// Hits before the cycle starts, these happen only once.
+    pub fn prefix_hits(&self) -> impl Iterator<Item = usize> + '_ {
+        self.hits
+            .iter()
+            .map(|(n, _)| *n)
+            .take_while(|&n| n < self.cycle.start)
+    }
+
+    /// Hits within the cycle, each repeats every `cycle.len` steps.
+    pub fn cycle_hits(&self) -> impl Iterator<Item = usize> + '_ {
+        self.hits
+            .iter()
+            .map(|(n, _)| *n)
+            .skip_while(|&n| n < self.cycle.start)
+    }
+}
+
+/// Floyd's tortoise and hare, keeps only two states in memory.
+pub fn floyd<S, F>(start: S, next: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: Fn(&S) -> S,
+{
+    let mut tortoise = next(&start);
+    let mut hare = next(&tortoise);
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&next(&hare));
+    }
+
+    // the hare is a multiple of the cycle length ahead,
+    // so both meet at the start of the cycle
+    let mut cycle_start = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        cycle_start += 1;
+    }
+
+    let mut len = 1;
+    hare = next(&tortoise);
+    while tortoise != hare {
+        hare = next(&hare);
+        len += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        len,
+    }
+}
+
+/// Brent's algorithm, keeps only two states in memory
+/// and needs fewer steps than [`floyd`].
+pub fn brent<S, F>(start: S, next: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: Fn(&S) -> S,
+{
+    // the tortoise waits at powers of two for the hare to come around
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = start.clone();
+    let mut hare = next(&start);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = next(&hare);
+        len += 1;
+    }
+
+    let mut cycle_start = 0;
+    tortoise = start.clone();
+    hare = start;
+    for _ in 0..len {
+        hare = next(&hare);
+    }
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        cycle_start += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        len,
+    }
+}
+
+/// Remembers the step of every state in a [`HashMap`],
+/// steps every state only once.
+pub fn hashed<S, F>(start: S, next: F) -> Cycle
+where
+    S: Eq + Hash,
+    F: Fn(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut state = start;
+    for n in 0.. {
+        if let Some(&cycle_start) = seen.get(&state) {
+            return Cycle {
+                start: cycle_start,
+                len: n - cycle_start,
+            };
+        }
+        let following = next(&state);
+        seen.insert(state, n);
+        state = following;
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `x -> x^2 + 1 mod m`, the sequence of Pollard's rho.
+    fn rho(m: u64) -> impl Fn(&u64) -> u64 {
+        move |x| (x * x + 1) % m
+    }
+
+    #[test]
+    fn test_strategies() {
+        let next = rho(2021);
+        let expected = hashed(0, &next);
+        assert_eq!(expected, Cycle { start: 10, len: 4 });
+        assert_eq!(floyd(0, &next), expected);
+        assert_eq!(brent(0, &next), expected);
+
+        // a fixpoint right away
+        let id = |x: &u8| *x;
+        assert_eq!(floyd(7, id), Cycle { start: 0, len: 1 });
+        assert_eq!(brent(7, id), Cycle { start: 0, len: 1 });
+        assert_eq!(hashed(7, id), Cycle { start: 0, len: 1 });
+
+        // prefix 3, then 3, 4, 5, 6, 3, ...
+        let lasso = |x: &u32| if *x == 6 { 3 } else { x + 1 };
+        assert_eq!(floyd(0, lasso), Cycle { start: 3, len: 4 });
+        assert_eq!(brent(0, lasso), Cycle { start: 3, len: 4 });
+        assert_eq!(hashed(0, lasso), Cycle { start: 3, len: 4 });
+    }
+
+    #[test]
+    fn test_against_each_other() {
+        for m in 2..300 {
+            for x in [0, 1, m / 2] {
+                let expected = hashed(x, rho(m));
+                assert_eq!(floyd(x, rho(m)), expected, "{m} {x}");
+                assert_eq!(brent(x, rho(m)), expected, "{m} {x}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_orbit() {
+        let lasso = |x: &u32| if *x == 6 { 3 } else { x + 1 };
+        let cycle = hashed(0, lasso);
+        let orbit = cycle.orbit(0, lasso, |x| x % 2 == 1);
+
+        assert_eq!(orbit.hits, vec![(1, 1), (3, 3), (5, 5)]);
+        assert_eq!(orbit.prefix_hits().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(orbit.cycle_hits().collect::<Vec<_>>(), vec![3, 5]);
+
+        let mut state = 0;
+        for n in 0..50 {
+            assert_eq!(orbit.is_hit(n), state % 2 == 1, "{n}");
+            state = lasso(&state);
+        }
+        assert_eq!(cycle.reduce(1_000_000_000), 4);
+    }
+}
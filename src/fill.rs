@@ -0,0 +1,271 @@
+use crate::bitgrid::BitGrid;
+use crate::{Dir4, Map, Pos};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only orthogonal neighbors are connected.
+    Four,
+    /// Diagonal neighbors are connected as well.
+    Eight,
+}
+
+impl Connectivity {
+    fn neighbors(self, pos: &Pos) -> Vec<Pos> {
+        match self {
+            Connectivity::Four => pos.neighbors4().collect(),
+            Connectivity::Eight => pos.neighbors8().collect(),
+        }
+    }
+}
+
+/// Connected regions of a [`Map`], see [`Map::label_regions`].
+#[derive(Debug, Clone)]
+pub struct Regions {
+    /// Region of every cell, numbered from 0 in reading order of their first cell.
+    pub labels: Map<usize>,
+    pub count: usize,
+}
+
+impl Regions {
+    /// Number of cells in region `label`.
+    pub fn area(&self, label: usize) -> usize {
+        self.labels.content.iter().filter(|&&l| l == label).count()
+    }
+
+    /// Number of cell edges between region `label` and
+    /// other regions or the border of the map.
+    pub fn perimeter(&self, label: usize) -> usize {
+        let labels = &self.labels;
+        (0..labels.content.len())
+            .filter(|&i| labels.content[i] == label)
+            .map(|i| Pos::from(((i % labels.width) as isize, (i / labels.width) as isize)))
+            .map(|pos| {
+                pos.neighbors4()
+                    .filter(|n| labels.get(n) != Some(&label))
+                    .count()
+            })
+            .sum()
+    }
+
+    pub fn areas(&self) -> Vec<usize> {
+        let mut areas = vec![0; self.count];
+        for &l in &self.labels.content {
+            areas[l] += 1;
+        }
+        areas
+    }
+}
+
+impl<T> Map<T> {
+    fn pos_of(&self, index: usize) -> Pos {
+        ((index % self.width) as isize, (index / self.width) as isize).into()
+    }
+
+    /// All cells reachable from `seeds` by orthogonal steps over cells for which `passable` holds.
+    /// Seeds that are not passable are ignored.
+    pub fn flood_fill<I, F>(&self, seeds: I, passable: F) -> BitGrid
+    where
+        I: IntoIterator<Item = Pos>,
+        F: Fn(&T) -> bool,
+    {
+        let mut filled = BitGrid::new(self.width, self.height);
+        let mut queue: VecDeque<Pos> = seeds
+            .into_iter()
+            .filter(|s| self.get(s).is_some_and(&passable))
+            .collect();
+        for s in &queue {
+            filled.set(s, true);
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            for next in pos.neighbors4() {
+                if filled.get(&next) == Some(false) && passable(self.get(&next).unwrap()) {
+                    filled.set(&next, true);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        filled
+    }
+
+    /// Splits the map into maximal regions in which neighboring cells `a` and `b`
+    /// are connected if `connected(a, b)` holds.
+    pub fn label_regions<F>(&self, connectivity: Connectivity, connected: F) -> Regions
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let mut labels: Vec<Option<usize>> = vec![None; self.content.len()];
+        let mut count = 0;
+
+        for start in 0..self.content.len() {
+            if labels[start].is_some() {
+                continue;
+            }
+
+            labels[start] = Some(count);
+            let mut stack = vec![self.pos_of(start)];
+            while let Some(pos) = stack.pop() {
+                let here = self.get(&pos).unwrap();
+                for next in connectivity.neighbors(&pos) {
+                    let Some(i) = self.get_index(&next) else {
+                        continue;
+                    };
+                    if labels[i].is_none() && connected(here, &self.content[i]) {
+                        labels[i] = Some(count);
+                        stack.push(next);
+                    }
+                }
+            }
+            count += 1;
+        }
+
+        Regions {
+            labels: Map {
+                content: labels.into_iter().map(Option::unwrap).collect(),
+                dims: [self.width, self.height],
+            },
+            count,
+        }
+    }
+
+    /// Cells reachable from outside the map, where movement may also squeeze
+    /// between two neighboring blocked cells unless they are linked.
+    /// `links(tile, dir)` tells whether a blocked `tile` is linked
+    /// to its neighbor in direction `dir`, e.g. by a pipe.
+    ///
+    /// Works on a grid of doubled resolution with a free border around,
+    /// where cell `(x, y)` becomes `(2x + 1, 2y + 1)` and
+    /// the cells in between are the gaps one can squeeze through.
+    pub fn squeeze_fill<B, L>(&self, blocked: B, links: L) -> BitGrid
+    where
+        B: Fn(&T) -> bool,
+        L: Fn(&T, Dir4) -> bool,
+    {
+        let (width, height) = (2 * self.width + 1, 2 * self.height + 1);
+        let fine = Map {
+            content: (0..width * height)
+                .map(|i| {
+                    let (x, y) = (i % width, i / width);
+                    let cell =
+                        |x: usize, y: usize| self.get(&(x as isize, y as isize).into()).unwrap();
+                    let linked = |a: &T, b: &T, dir: Dir4| {
+                        blocked(a) && blocked(b) && links(a, dir) && links(b, dir.opp())
+                    };
+
+                    match (x % 2, y % 2) {
+                        (1, 1) => blocked(cell(x / 2, y / 2)),
+                        (0, 1) if x > 0 && x < width - 1 => {
+                            linked(cell(x / 2 - 1, y / 2), cell(x / 2, y / 2), Dir4::East)
+                        }
+                        (1, 0) if y > 0 && y < height - 1 => {
+                            linked(cell(x / 2, y / 2 - 1), cell(x / 2, y / 2), Dir4::South)
+                        }
+                        _ => false,
+                    }
+                })
+                .collect(),
+            dims: [width, height],
+        };
+
+        let outside = fine.flood_fill([(0, 0).into()], |&wall| !wall);
+        let mut reached = BitGrid::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let fine_pos = ((2 * x + 1) as isize, (2 * y + 1) as isize).into();
+                if outside.get(&fine_pos) == Some(true) {
+                    reached.set(&(x as isize, y as isize).into(), true);
+                }
+            }
+        }
+        reached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const ROOMS: &str = "#######
+#..#..#
+#..#..#
+####..#
+#.#####
+";
+
+    #[test]
+    fn test_flood_fill() {
+        let map: Map<char> = Map::from_str(ROOMS).unwrap();
+
+        let filled = map.flood_fill([(1, 1).into()], |&c| c == '.');
+        assert_eq!(filled.count_ones(), 4);
+        assert_eq!(filled.get(&(2, 2).into()), Some(true));
+        assert_eq!(filled.get(&(4, 1).into()), Some(false));
+
+        let filled = map.flood_fill([(1, 1).into(), (5, 3).into(), (0, 0).into()], |&c| c == '.');
+        assert_eq!(filled.count_ones(), 10);
+    }
+
+    #[test]
+    fn test_label_regions() {
+        let map: Map<char> = Map::from_str(ROOMS).unwrap();
+
+        let regions = map.label_regions(Connectivity::Four, |a, b| a == b);
+        assert_eq!(regions.count, 4);
+        assert_eq!(regions.labels.get(&(0, 0).into()), Some(&0));
+        assert_eq!(regions.areas(), vec![24, 4, 6, 1]);
+        assert_eq!(regions.perimeter(1), 8);
+        assert_eq!(regions.perimeter(2), 10);
+        assert_eq!(regions.perimeter(3), 4);
+
+        let diagonal: Map<char> = Map::from_str("#.\n.#\n").unwrap();
+        assert_eq!(
+            diagonal
+                .label_regions(Connectivity::Four, |a, b| a == b)
+                .count,
+            4
+        );
+        assert_eq!(
+            diagonal
+                .label_regions(Connectivity::Eight, |a, b| a == b)
+                .count,
+            2
+        );
+    }
+
+    #[test]
+    fn test_squeeze_fill() {
+        let map: Map<char> = Map::from_str(
+            "..........
+.F------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........
+",
+        )
+        .unwrap();
+        let on_loop = |&c: &char| c != '.';
+        let links = |&c: &char, dir: Dir4| match dir {
+            Dir4::North => "|LJ".contains(c),
+            Dir4::East => "-LF".contains(c),
+            Dir4::South => "|7F".contains(c),
+            Dir4::West => "-J7".contains(c),
+        };
+
+        let outside = map.squeeze_fill(on_loop, links);
+        let free = map.content.iter().filter(|c| !on_loop(c)).count();
+        assert_eq!(free - outside.count_ones(), 4);
+        assert_eq!(outside.get(&(3, 3).into()), Some(true));
+        assert_eq!(outside.get(&(2, 6).into()), Some(false));
+
+        // without squeezing, the middle of the loop looks enclosed
+        let outside = map.flood_fill([(0, 0).into()], |c| !on_loop(c));
+        assert_eq!(free - outside.count_ones(), 12);
+    }
+}
@@ -0,0 +1,310 @@
+use std::ops::{Add, Range, Sub};
+
+/// Set of values stored as sorted, disjoint, non-adjacent half-open ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+}
+
+impl<T: Ord + Copy> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Ord + Copy> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> IntervalSet<T> {
+        let mut ranges: Vec<_> = iter.into_iter().filter(|r| r.start < r.end).collect();
+        ranges.sort_unstable_by_key(|r| r.start);
+        IntervalSet {
+            ranges: merge(ranges),
+        }
+    }
+}
+
+/// Joins overlapping and adjacent ranges of a list sorted by start.
+fn merge<T: Ord + Copy>(sorted: Vec<Range<T>>) -> Vec<Range<T>> {
+    let mut merged: Vec<Range<T>> = Vec::with_capacity(sorted.len());
+    for r in sorted {
+        match merged.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => merged.push(r),
+        }
+    }
+    merged
+}
+
+impl<T: Ord + Copy> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Range<T>> {
+        self.ranges.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    /// The smallest value larger than all elements.
+    pub fn sup(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end)
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        // index of the first range starting after x
+        let i = self.ranges.partition_point(|r| r.start <= *x);
+        i > 0 && *x < self.ranges[i - 1].end
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+
+        let mut joined = range;
+        if first < last {
+            joined.start = joined.start.min(self.ranges[first].start);
+            joined.end = joined.end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [joined]);
+    }
+
+    pub fn remove(&mut self, range: Range<T>) {
+        *self = self.difference(&range.into());
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::with_capacity(self.ranges.len() + other.ranges.len());
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        loop {
+            let next = match (a.peek(), b.peek()) {
+                (Some(ra), Some(rb)) if ra.start <= rb.start => a.next(),
+                (Some(_), Some(_)) => b.next(),
+                (Some(_), None) => a.next(),
+                (None, Some(_)) => b.next(),
+                (None, None) => break,
+            };
+            ranges.push(next.unwrap().clone());
+        }
+        IntervalSet {
+            ranges: merge(ranges),
+        }
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// Elements of `bounds` that are not in the set.
+    pub fn complement(&self, bounds: Range<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let mut start = bounds.start;
+        for r in &self.ranges {
+            if r.start > start {
+                ranges.push(start..r.start.min(bounds.end));
+            }
+            start = start.max(r.end);
+            if start >= bounds.end {
+                break;
+            }
+        }
+        if start < bounds.end {
+            ranges.push(start..bounds.end);
+        }
+        ranges.retain(|r| r.start < r.end);
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        match (self.min(), self.sup()) {
+            (Some(min), Some(sup)) => self.intersection(&other.complement(min..sup)),
+            _ => IntervalSet::new(),
+        }
+    }
+
+    /// Splits into the elements smaller than `at` and the rest.
+    pub fn split_at(&self, at: T) -> (IntervalSet<T>, IntervalSet<T>) {
+        let mut below = Vec::new();
+        let mut above = Vec::new();
+        for r in &self.ranges {
+            if r.end <= at {
+                below.push(r.clone());
+            } else if r.start >= at {
+                above.push(r.clone());
+            } else {
+                below.push(r.start..at);
+                above.push(at..r.end);
+            }
+        }
+        (IntervalSet { ranges: below }, IntervalSet { ranges: above })
+    }
+
+    /// Number of elements, for integer `T`.
+    pub fn measure(&self) -> T
+    where
+        T: Default + Add<Output = T> + Sub<Output = T>,
+    {
+        self.ranges
+            .iter()
+            .fold(T::default(), |acc, r| acc + (r.end - r.start))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    use crate::Lcg;
+
+    fn random_set(rng: &mut Lcg) -> (IntervalSet<u64>, u64) {
+        let mut set = IntervalSet::new();
+        let mut bits = 0;
+        for _ in 0..rng.next(5) {
+            let start = rng.next(64);
+            let end = start + rng.next(64 - start + 1);
+            set.insert(start..end);
+            bits |= mask(start..end);
+        }
+        (set, bits)
+    }
+
+    fn mask(r: Range<u64>) -> u64 {
+        (r.start..r.end).fold(0, |acc, i| acc | 1 << i)
+    }
+
+    fn to_bits(set: &IntervalSet<u64>) -> u64 {
+        set.iter().fold(0, |acc, r| acc | mask(r.clone()))
+    }
+
+    fn is_normalized(set: &IntervalSet<u64>) -> bool {
+        set.ranges().iter().all(|r| r.start < r.end)
+            && set.ranges().windows(2).all(|w| w[0].end < w[1].start)
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut set = IntervalSet::new();
+        set.insert(5..10);
+        set.insert(0..2);
+        set.insert(12..15);
+        assert_eq!(set.ranges(), &[0..2, 5..10, 12..15]);
+        set.insert(10..12);
+        assert_eq!(set.ranges(), &[0..2, 5..15]);
+        set.insert(3..3);
+        set.insert(1..6);
+        assert_eq!(set.ranges(), &[0..15]);
+
+        assert!(set.contains(&0));
+        assert!(!set.contains(&15));
+        assert_eq!(set.measure(), 15);
+    }
+
+    #[test]
+    fn test_operations() {
+        let a: IntervalSet<i32> = [0..10, 20..30].into_iter().collect();
+        let b: IntervalSet<i32> = [5..25].into_iter().collect();
+
+        assert_eq!(a.union(&b).ranges(), &[0..30]);
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 25..30]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20]);
+        assert_eq!(a.complement(-5..35).ranges(), &[-5..0, 10..20, 30..35]);
+        assert_eq!(a.complement(12..18).ranges(), &[12..18]);
+
+        let (below, above) = a.split_at(25);
+        assert_eq!(below.ranges(), &[0..10, 20..25]);
+        assert_eq!(above.ranges(), &[25..30]);
+
+        // the edge case of day05's old `containment`: ranges sharing an end
+        assert_eq!(
+            IntervalSet::from(3..10)
+                .intersection(&(5..10).into())
+                .ranges(),
+            &[5..10]
+        );
+        assert!(IntervalSet::from(5..10)
+            .difference(&(3..10).into())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_against_bitset() {
+        let mut rng = Lcg(2023);
+        for _ in 0..2000 {
+            let (a, a_bits) = random_set(&mut rng);
+            let (b, b_bits) = random_set(&mut rng);
+            let at = rng.next(65);
+            let bounds = rng.next(64)..rng.next(65);
+
+            assert_eq!(to_bits(&a), a_bits);
+            for set in [
+                a.union(&b),
+                a.intersection(&b),
+                a.difference(&b),
+                a.complement(bounds.clone()),
+            ] {
+                assert!(is_normalized(&set), "{:?}", set);
+            }
+
+            assert_eq!(to_bits(&a.union(&b)), a_bits | b_bits);
+            assert_eq!(to_bits(&a.intersection(&b)), a_bits & b_bits);
+            assert_eq!(to_bits(&a.difference(&b)), a_bits & !b_bits);
+            assert_eq!(
+                to_bits(&a.complement(bounds.clone())),
+                !a_bits & mask(bounds.clone())
+            );
+
+            let (below, above) = a.split_at(at);
+            assert_eq!(to_bits(&below), a_bits & mask(0..at));
+            assert_eq!(to_bits(&above), a_bits & !mask(0..at));
+
+            assert_eq!(a.measure(), a_bits.count_ones() as u64);
+            for x in 0..64 {
+                assert_eq!(a.contains(&x), a_bits & 1 << x != 0);
+            }
+
+            let mut removed = a.clone();
+            removed.remove(bounds.clone());
+            assert_eq!(to_bits(&removed), a_bits & !mask(bounds));
+        }
+    }
+}
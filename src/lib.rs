@@ -1,7 +1,33 @@
 use curl::easy::{Easy, HttpVersion};
+use std::error::Error;
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
-use std::fmt;
+use std::str::FromStr;
+
+pub mod automaton;
+pub mod bigint;
+pub mod bitgrid;
+pub mod cards;
+pub mod cycle;
+pub mod fill;
+pub mod interval;
+pub mod math;
+pub mod multiset;
+pub mod ndim;
+pub mod piecewise;
+pub mod race;
+pub mod rational;
+pub mod scanner;
+pub mod scratchcard;
+pub mod search;
+pub mod sequence;
+pub mod sparse;
+
+pub use bigint::BigInt;
+pub use interval::IntervalSet;
+pub use ndim::{GridN, PosN};
+pub use rational::Rational;
 
 pub fn get_input(year: &str, day: &str) -> String {
     let mut input = String::new();
@@ -42,59 +68,297 @@ pub fn get_input(year: &str, day: &str) -> String {
     input
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Pos {
-    pub x: isize,
-    pub y: isize,
-}
+/// Position on a 2-D map, with `y` pointing down.
+pub type Pos = PosN<2>;
 
 impl From<(isize, isize)> for Pos {
     fn from(f: (isize, isize)) -> Pos {
-        Pos { x: f.0, y: f.1 }
+        PosN([f.0, f.1])
     }
 }
 
-struct Map<T> {
-    content: Vec<T>,
-    width: usize,
-    height: usize,
+impl Pos {
+    pub fn go(&mut self, dir: Dir4) -> &mut Pos {
+        *self += dir.offset();
+        self
+    }
+
+    pub fn neighbors4(&self) -> impl Iterator<Item = Pos> + '_ {
+        Dir4::ALL.into_iter().map(move |d| self + &d.offset())
+    }
+
+    pub fn neighbors8(&self) -> impl Iterator<Item = Pos> + '_ {
+        Dir8::ALL.into_iter().map(move |d| self + &d.offset())
+    }
 }
 
-impl<T: TryFrom<char>> Map<T> {
-    fn from_str(s: &str) -> Map<T> {
-        let mut l = s.lines();
-        let width = l.next().unwrap().len();
-        let height = l.count() + 1;
+/// The four orthogonal directions, with y pointing down (south).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir4 {
+    North,
+    East,
+    South,
+    West,
+}
 
-        let content = s
-            .replace("\r", "")
-            .chars()
-            .filter_map(|c| T::try_from(c).ok())
-            .collect();
+impl Dir4 {
+    /// Clockwise, starting north.
+    pub const ALL: [Dir4; 4] = [Dir4::North, Dir4::East, Dir4::South, Dir4::West];
 
-        Map {
+    pub fn opp(&self) -> Dir4 {
+        self.turn_right().turn_right()
+    }
+
+    pub fn turn_left(&self) -> Dir4 {
+        Dir4::ALL[(*self as usize + 3) % 4]
+    }
+
+    pub fn turn_right(&self) -> Dir4 {
+        Dir4::ALL[(*self as usize + 1) % 4]
+    }
+
+    pub fn offset(&self) -> Pos {
+        use Dir4::*;
+        match self {
+            North => (0, -1).into(),
+            East => (1, 0).into(),
+            South => (0, 1).into(),
+            West => (-1, 0).into(),
+        }
+    }
+}
+
+/// The four orthogonal and four diagonal directions, with y pointing down (south).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Dir8 {
+    /// Clockwise, starting north.
+    pub const ALL: [Dir8; 8] = [
+        Dir8::North,
+        Dir8::NorthEast,
+        Dir8::East,
+        Dir8::SouthEast,
+        Dir8::South,
+        Dir8::SouthWest,
+        Dir8::West,
+        Dir8::NorthWest,
+    ];
+
+    pub fn opp(&self) -> Dir8 {
+        Dir8::ALL[(*self as usize + 4) % 8]
+    }
+
+    /// Turns by 45°.
+    pub fn turn_left(&self) -> Dir8 {
+        Dir8::ALL[(*self as usize + 7) % 8]
+    }
+
+    /// Turns by 45°.
+    pub fn turn_right(&self) -> Dir8 {
+        Dir8::ALL[(*self as usize + 1) % 8]
+    }
+
+    pub fn offset(&self) -> Pos {
+        use Dir8::*;
+        match self {
+            North => (0, -1).into(),
+            NorthEast => (1, -1).into(),
+            East => (1, 0).into(),
+            SouthEast => (1, 1).into(),
+            South => (0, 1).into(),
+            SouthWest => (-1, 1).into(),
+            West => (-1, 0).into(),
+            NorthWest => (-1, -1).into(),
+        }
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(d: Dir4) -> Dir8 {
+        Dir8::ALL[d as usize * 2]
+    }
+}
+
+/// A rectangular 2-D grid, rows stored top to bottom.
+pub type Map<T> = GridN<T, 2>;
+
+impl<T: TryFrom<char>> FromStr for Map<T> {
+    type Err = Box<dyn Error + 'static>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut content = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for line in s.lines() {
+            let before = content.len();
+            for (x, c) in line.chars().enumerate() {
+                let el = T::try_from(c)
+                    .map_err(|_| format!("Invalid character {c:?} at ({x}, {height})"))?;
+                content.push(el);
+            }
+
+            let row_width = content.len() - before;
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(format!(
+                    "Row {height} has width {row_width}, expected {}",
+                    width.unwrap()
+                )
+                .into());
+            }
+            height += 1;
+        }
+
+        Ok(Map {
             content,
+            dims: [width.ok_or("Map is empty")?, height],
+        })
+    }
+}
+
+impl<T> Map<T> {
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.content[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Borrow the `width` x `height` rectangle whose upper left corner is `corner`.
+    /// Returns `None` if the rectangle does not fit into the map.
+    pub fn view(&self, corner: &Pos, width: usize, height: usize) -> Option<MapView<'_, T>> {
+        if corner.x < 0
+            || corner.y < 0
+            || corner.x as usize + width > self.width
+            || corner.y as usize + height > self.height
+        {
+            return None;
+        }
+
+        Some(MapView {
+            map: self,
+            corner: *corner,
             width,
             height,
+        })
+    }
+
+    /// Inserts a row before row `y`, shifting all rows below down.
+    ///
+    /// # Panics
+    /// If `y > height` or the row does not have `width` elements.
+    pub fn insert_row(&mut self, y: usize, row: Vec<T>) {
+        assert!(y <= self.height, "Row {y} out of bounds");
+        assert_eq!(row.len(), self.width, "Row has wrong width");
+
+        let at = y * self.width;
+        self.content.splice(at..at, row);
+        self.height += 1;
+    }
+
+    /// Inserts a column before column `x`, shifting all columns to the right.
+    ///
+    /// # Panics
+    /// If `x > width` or the column does not have `height` elements.
+    pub fn insert_col(&mut self, x: usize, col: Vec<T>) {
+        assert!(x <= self.width, "Column {x} out of bounds");
+        assert_eq!(col.len(), self.height, "Column has wrong height");
+
+        // insert from the bottom, so the indices above stay valid
+        for (y, el) in col.into_iter().enumerate().rev() {
+            self.content.insert(y * self.width + x, el);
         }
+        self.width += 1;
+    }
+
+    /// Removes row `y` and returns it.
+    ///
+    /// # Panics
+    /// If `y >= height`.
+    pub fn remove_row(&mut self, y: usize) -> Vec<T> {
+        assert!(y < self.height, "Row {y} out of bounds");
+
+        self.height -= 1;
+        self.content
+            .drain(y * self.width..(y + 1) * self.width)
+            .collect()
+    }
+
+    /// Removes column `x` and returns it.
+    ///
+    /// # Panics
+    /// If `x >= width`.
+    pub fn remove_col(&mut self, x: usize) -> Vec<T> {
+        assert!(x < self.width, "Column {x} out of bounds");
+
+        let mut col: Vec<_> = (0..self.height)
+            .rev()
+            .map(|y| self.content.remove(y * self.width + x))
+            .collect();
+        col.reverse();
+        self.width -= 1;
+        col
     }
 }
 
-impl<T> Map<T> {
-    fn get_index(&self, pos: &Pos) -> Option<usize> {
-        if pos.x < self.width as isize && pos.x >= 0 && pos.y < self.height as isize && pos.y >= 0 {
-            return Some(pos.x as usize + pos.y as usize * self.width);
+impl<T: Clone> Map<T> {
+    /// Builds a new `width` x `height` map, where `source(x, y)` is
+    /// the position in `self` the element at `(x, y)` is copied from.
+    fn remap<F: Fn(usize, usize) -> (usize, usize)>(
+        &self,
+        width: usize,
+        height: usize,
+        source: F,
+    ) -> Map<T> {
+        let content = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (sx, sy) = source(x, y);
+                self.content[sx + sy * self.width].clone()
+            })
+            .collect();
+
+        Map {
+            content,
+            dims: [width, height],
         }
-        None
     }
 
-    fn get(&self, pos: &Pos) -> Option<&T> {
-        self.content.get(self.get_index(pos)?)
+    /// Rotates by 90° clockwise.
+    pub fn rotate_90(&self) -> Map<T> {
+        self.remap(self.height, self.width, |x, y| (y, self.height - 1 - x))
+    }
+
+    pub fn rotate_180(&self) -> Map<T> {
+        self.remap(self.width, self.height, |x, y| {
+            (self.width - 1 - x, self.height - 1 - y)
+        })
+    }
+
+    /// Rotates by 270° clockwise, i.e. 90° counter-clockwise.
+    pub fn rotate_270(&self) -> Map<T> {
+        self.remap(self.height, self.width, |x, y| (self.width - 1 - y, x))
+    }
+
+    /// Mirrors left and right.
+    pub fn flip_horizontal(&self) -> Map<T> {
+        self.remap(self.width, self.height, |x, y| (self.width - 1 - x, y))
+    }
+
+    /// Mirrors top and bottom.
+    pub fn flip_vertical(&self) -> Map<T> {
+        self.remap(self.width, self.height, |x, y| (x, self.height - 1 - y))
     }
 
-    fn get_mut(&mut self, pos: &Pos) -> Option<&mut T> {
-        let index = self.get_index(pos)?;
-        self.content.get_mut(index)
+    pub fn transpose(&self) -> Map<T> {
+        self.remap(self.height, self.width, |x, y| (y, x))
     }
 }
 
@@ -104,12 +368,98 @@ impl<T: fmt::Display> fmt::Display for Map<T> {
             for x in 0..self.width as isize {
                 write!(f, "{}", self.get(&(x, y).into()).unwrap())?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
+/// A borrowed rectangular part of a [`Map`].
+/// Positions are relative to the upper left corner of the view.
+pub struct MapView<'a, T> {
+    map: &'a Map<T>,
+    corner: Pos,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<'a, T> MapView<'a, T> {
+    pub fn get(&self, pos: &Pos) -> Option<&'a T> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width as isize || pos.y >= self.height as isize {
+            return None;
+        }
+
+        self.map
+            .get(&(self.corner.x + pos.x, self.corner.y + pos.y).into())
+    }
+
+    pub fn row(&self, y: usize) -> &'a [T] {
+        let start = self.corner.x as usize;
+        &self.map.row(self.corner.y as usize + y)[start..start + self.width]
+    }
+
+    /// Borrow a smaller rectangle of this view, see [`Map::view`].
+    pub fn view(&self, corner: &Pos, width: usize, height: usize) -> Option<MapView<'a, T>> {
+        if corner.x < 0
+            || corner.y < 0
+            || corner.x as usize + width > self.width
+            || corner.y as usize + height > self.height
+        {
+            return None;
+        }
+
+        Some(MapView {
+            map: self.map,
+            corner: (self.corner.x + corner.x, self.corner.y + corner.y).into(),
+            width,
+            height,
+        })
+    }
+}
+
+impl<T: Clone> MapView<'_, T> {
+    pub fn to_map(&self) -> Map<T> {
+        Map {
+            content: (0..self.height)
+                .flat_map(|y| self.row(y).to_vec())
+                .collect(),
+            dims: [self.width, self.height],
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for MapView<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for y in 0..self.height {
+            for el in self.row(y) {
+                write!(f, "{}", el)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tiny linear congruential generator, good enough to produce test cases.
+#[cfg(test)]
+pub(crate) struct Lcg(pub u64);
+
+#[cfg(test)]
+impl Lcg {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0
+    }
+
+    /// A number in `0..max`.
+    pub fn next(&mut self, max: u64) -> u64 {
+        (self.next_u64() >> 33) % max
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,4 +471,148 @@ mod tests {
             fs::read_to_string("inputs/day01").unwrap()
         );
     }
+
+    #[test]
+    fn test_pos_arithmetic() {
+        let a = Pos::from((1, 2));
+        let b = Pos::from((-3, 5));
+
+        assert_eq!(a + b, (-2, 7).into());
+        assert_eq!(a - b, (4, -3).into());
+        assert_eq!(&b * 2, (-6, 10).into());
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+
+        assert!(Pos::from((5, 0)) < Pos::from((0, 1)));
+        let mut sorted = vec![Pos::from((1, 1)), (0, 1).into(), (3, 0).into()];
+        sorted.sort();
+        assert_eq!(sorted, vec![(3, 0).into(), (0, 1).into(), (1, 1).into()]);
+    }
+
+    #[test]
+    fn test_dirs() {
+        for d in Dir4::ALL {
+            assert_eq!(d.opp().opp(), d);
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_right(), d.opp());
+            assert_eq!(d.offset() + d.opp().offset(), (0, 0).into());
+            assert_eq!(Dir8::from(d).offset(), d.offset());
+        }
+        assert_eq!(Dir4::North.turn_right(), Dir4::East);
+        assert_eq!(Dir4::North.turn_left(), Dir4::West);
+
+        for d in Dir8::ALL {
+            assert_eq!(
+                d.turn_right().turn_right().turn_right().turn_right(),
+                d.opp()
+            );
+            assert_eq!(d.offset() + d.opp().offset(), (0, 0).into());
+        }
+        assert_eq!(Dir8::NorthWest.turn_right(), Dir8::North);
+
+        let mut pos = Pos::from((0, 0));
+        pos.go(Dir4::East).go(Dir4::South).go(Dir4::South);
+        assert_eq!(pos, (1, 2).into());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let pos = Pos::from((0, 0));
+        assert_eq!(pos.neighbors4().count(), 4);
+        assert!(pos.neighbors4().all(|n| n.manhattan(&pos) == 1));
+        assert_eq!(pos.neighbors8().count(), 8);
+        assert!(pos.neighbors8().all(|n| n.chebyshev(&pos) == 1));
+
+        let set: std::collections::HashSet<Pos> =
+            pos.neighbors8().chain(pos.neighbors4()).collect();
+        assert_eq!(set.len(), 8);
+    }
+
+    const GRID: &str = "abc
+def
+";
+
+    fn grid() -> Map<char> {
+        Map::from_str(GRID).unwrap()
+    }
+
+    #[test]
+    fn test_map_parse() {
+        let map = grid();
+        assert_eq!(map.width, 3);
+        assert_eq!(map.height, 2);
+        assert_eq!(map.get(&(2, 1).into()), Some(&'f'));
+        assert_eq!(map.to_string(), GRID);
+
+        assert!(Map::<char>::from_str("abc\nde\n").is_err());
+        let err = Map::<u8>::from_str("ab\nc€\n").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid character '€' at (1, 1)");
+    }
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(grid().rotate_90().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid().rotate_180().to_string(), "fed\ncba\n");
+        assert_eq!(grid().rotate_270().to_string(), "cf\nbe\nad\n");
+        assert_eq!(
+            grid()
+                .rotate_90()
+                .rotate_90()
+                .rotate_90()
+                .rotate_90()
+                .to_string(),
+            GRID
+        );
+    }
+
+    #[test]
+    fn test_flip_transpose() {
+        assert_eq!(grid().flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(grid().flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(grid().transpose().to_string(), "ad\nbe\ncf\n");
+    }
+
+    #[test]
+    fn test_view() {
+        let map: Map<char> = Map::from_str(
+            "abcd
+efgh
+ijkl
+",
+        )
+        .unwrap();
+
+        let view = map.view(&(1, 1).into(), 2, 2).unwrap();
+        assert_eq!(view.to_string(), "fg\njk\n");
+        assert_eq!(view.get(&(1, 0).into()), Some(&'g'));
+        assert_eq!(view.get(&(2, 0).into()), None);
+        assert_eq!(view.view(&(1, 1).into(), 1, 1).unwrap().to_string(), "k\n");
+        assert_eq!(view.to_map().rotate_90().to_string(), "jf\nkg\n");
+
+        assert!(map.view(&(3, 0).into(), 2, 1).is_none());
+        assert!(map.view(&(-1, 0).into(), 1, 1).is_none());
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut map = grid();
+
+        map.insert_row(1, vec!['x', 'y', 'z']);
+        assert_eq!(map.to_string(), "abc\nxyz\ndef\n");
+        map.insert_col(3, vec!['1', '2', '3']);
+        assert_eq!(map.to_string(), "abc1\nxyz2\ndef3\n");
+        map.insert_col(0, vec!['.', '.', '.']);
+        assert_eq!(map.to_string(), ".abc1\n.xyz2\n.def3\n");
+
+        assert_eq!(map.remove_col(2), vec!['b', 'y', 'e']);
+        assert_eq!(map.remove_row(0), vec!['.', 'a', 'c', '1']);
+        assert_eq!(map.to_string(), ".xz2\n.df3\n");
+        assert_eq!((map.width, map.height), (4, 2));
+    }
 }
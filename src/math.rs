@@ -0,0 +1,214 @@
+/// Greatest common divisor, `gcd(0, 0) == 0`.
+pub fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b > 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, `None` on overflow.
+pub fn lcm(a: usize, b: usize) -> Option<usize> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+pub fn gcd_all<I: IntoIterator<Item = usize>>(nums: I) -> usize {
+    nums.into_iter().fold(0, gcd)
+}
+
+/// Least common multiple of all numbers, `None` on overflow.
+pub fn lcm_all<I: IntoIterator<Item = usize>>(nums: I) -> Option<usize> {
+    nums.into_iter().try_fold(1, lcm)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b) = a * x + b * y` and `g >= 0`,
+/// or `None` if `g` is `2^63`, which only happens for `i64::MIN` and `0` or itself.
+pub fn extended_gcd(a: i64, b: i64) -> Option<(i64, i64, i64)> {
+    let (mut r0, mut r1) = (a as i128, b as i128);
+    let (mut x0, mut x1) = (1i128, 0i128);
+    let (mut y0, mut y1) = (0i128, 1i128);
+
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < 0 {
+        (r0, x0, y0) = (-r0, -x0, -y0);
+    }
+    Some((
+        i64::try_from(r0).ok()?,
+        i64::try_from(x0).ok()?,
+        i64::try_from(y0).ok()?,
+    ))
+}
+
+/// The `x` in `0..m` with `a * x = 1 (mod m)`, if `a` and `m` are coprime.
+/// `None` if `m` is not positive.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a, m)?;
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Solves the system of congruences `x = r (mod m)` for every `(r, m)`,
+/// the moduli do not need to be coprime.
+/// Returns `(r, m)` such that the solutions are exactly `r + k * m`,
+/// or `None` if there is no solution, a modulus is not positive
+/// or the combined modulus overflows.
+pub fn crt<I: IntoIterator<Item = (i64, i64)>>(congruences: I) -> Option<(i64, i64)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(r1, m1), (r2, m2)| {
+            if m2 <= 0 {
+                return None;
+            }
+            let (g, p, _) = extended_gcd(m1, m2)?;
+            let diff = r2 as i128 - r1 as i128;
+            if diff % g as i128 != 0 {
+                return None;
+            }
+
+            let m = m1 as i128 / g as i128 * m2 as i128;
+            // m1 * p = g (mod m2), so r1 + m1 * p * diff / g = r2 (mod m2)
+            let k = (diff / g as i128 * p as i128).rem_euclid(m2 as i128 / g as i128);
+            let r = (r1 as i128 + m1 as i128 * k).rem_euclid(m);
+            Some((i64::try_from(r).ok()?, i64::try_from(m).ok()?))
+        })
+}
+
+/// `base^exp mod modulus`.
+///
+/// # Panics
+/// If `modulus` is zero.
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let m = modulus as u128;
+    let mut base = base as u128 % m;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// The largest `r` with `r * r <= n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // Newton's method from above, decreases until it reaches the floor
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(usize::MAX, usize::MAX - 1), None);
+
+        assert_eq!(gcd_all([12, 18, 27]), 3);
+        assert_eq!(gcd_all([]), 0);
+        assert_eq!(lcm_all([2, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all([]), Some(1));
+        assert_eq!(lcm_all([1_000_000_007, 1_000_000_009, 998_244_353]), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [
+            (240, 46),
+            (-240, 46),
+            (17, -5),
+            (0, 9),
+            (9, 0),
+            (i64::MAX, 2),
+            (i64::MIN, 6),
+            (i64::MIN, 1),
+        ] {
+            let (g, x, y) = extended_gcd(a, b).unwrap();
+            assert_eq!(
+                g as usize,
+                gcd(a.unsigned_abs() as usize, b.unsigned_abs() as usize)
+            );
+            assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+        }
+        // the gcd 2^63 doesn't fit
+        assert_eq!(extended_gcd(i64::MIN, 0), None);
+        assert_eq!(extended_gcd(0, i64::MIN), None);
+        assert_eq!(extended_gcd(i64::MIN, i64::MIN), None);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(3, 0), None);
+        assert_eq!(mod_inverse(3, -11), None);
+        assert_eq!(mod_inverse(i64::MIN, 1), Some(0));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // non coprime moduli
+        assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        // negative and unreduced residues
+        assert_eq!(crt([(-1, 4), (9, 6)]), Some((3, 12)));
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(2, 3), (0, 0)]), None);
+        assert_eq!(crt([(2, -3)]), None);
+
+        let big = 1_000_000_007;
+        assert_eq!(
+            crt([(1, big), (2, big + 2)]),
+            Some((500000007500000029, big * (big + 2)))
+        );
+        assert_eq!(crt([(0, big), (0, big + 2), (0, big + 4)]), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 7), 1);
+        assert_eq!(mod_pow(3, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, 1_000_000_007), 254_368_884);
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for n in 0..10_000u128 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "{n}");
+        }
+        for r in [1u128 << 32, (1 << 63) - 1, u64::MAX as u128] {
+            assert_eq!(isqrt(r * r), r);
+            assert_eq!(isqrt(r * r - 1), r - 1);
+            assert_eq!(isqrt(r * r + 2 * r), r);
+        }
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+    }
+}
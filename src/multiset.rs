@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Counts of named items, like the cubes of each colour in a bag.
+/// Missing names count zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Multiset {
+    /// Only nonzero counts, so equal multisets compare equal.
+    counts: BTreeMap<String, usize>,
+}
+
+impl Multiset {
+    pub fn new() -> Multiset {
+        Multiset::default()
+    }
+
+    pub fn count(&self, name: &str) -> usize {
+        self.counts.get(name).copied().unwrap_or(0)
+    }
+
+    /// Adds `n` items called `name`.
+    pub fn insert(&mut self, name: &str, n: usize) {
+        if n > 0 {
+            *self.counts.entry(name.to_string()).or_insert(0) += n;
+        }
+    }
+
+    /// Names with a nonzero count and their counts, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> + '_ {
+        self.counts.iter().map(|(name, &n)| (name.as_str(), n))
+    }
+
+    /// Total number of items.
+    pub fn len(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Whether `other` has at least as many of every item, so this could be drawn from it.
+    pub fn is_subset(&self, other: &Multiset) -> bool {
+        self.iter().all(|(name, n)| n <= other.count(name))
+    }
+
+    /// The smallest multiset containing both, taking the larger count of every item.
+    pub fn union(&self, other: &Multiset) -> Multiset {
+        let mut counts = self.counts.clone();
+        for (name, n) in other.iter() {
+            let c = counts.entry(name.to_string()).or_insert(0);
+            *c = (*c).max(n);
+        }
+        Multiset { counts }
+    }
+
+    /// Product of the counts of `names`, zero if one of them is missing.
+    pub fn product<'a, I: IntoIterator<Item = &'a str>>(&self, names: I) -> usize {
+        names.into_iter().map(|name| self.count(name)).product()
+    }
+}
+
+impl<'a> FromIterator<(&'a str, usize)> for Multiset {
+    fn from_iter<I: IntoIterator<Item = (&'a str, usize)>>(iter: I) -> Multiset {
+        let mut set = Multiset::new();
+        for (name, n) in iter {
+            set.insert(name, n);
+        }
+        set
+    }
+}
+
+/// Parses counts like `3 blue, 4 red`, repeated names add up.
+impl FromStr for Multiset {
+    type Err = Box<dyn Error + 'static>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = Multiset::new();
+        for item in s.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let (n, name) = item
+                .split_once(' ')
+                .ok_or_else(|| format!("Expected count and name, got {item:?}"))?;
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(format!("Invalid name in {item:?}").into());
+            }
+            set.insert(name, n.parse()?);
+        }
+        Ok(set)
+    }
+}
+
+impl fmt::Display for Multiset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let items: Vec<String> = self.iter().map(|(name, n)| format!("{n} {name}")).collect();
+        write!(f, "{}", items.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let set: Multiset = "3 blue, 4 red, 1 blue".parse().unwrap();
+        assert_eq!(set.count("blue"), 4);
+        assert_eq!(set.count("red"), 4);
+        assert_eq!(set.count("green"), 0);
+        assert_eq!(set.len(), 8);
+        assert_eq!(set.to_string(), "4 blue, 4 red");
+        assert_eq!(
+            set,
+            [("red", 4), ("blue", 4), ("green", 0)]
+                .into_iter()
+                .collect()
+        );
+
+        assert!("".parse::<Multiset>().unwrap().is_empty());
+        assert_eq!("0 red".parse::<Multiset>().unwrap(), Multiset::new());
+        assert!("blue".parse::<Multiset>().is_err());
+        assert!("x blue".parse::<Multiset>().is_err());
+        assert!("3 dark blue".parse::<Multiset>().is_err());
+    }
+
+    #[test]
+    fn test_queries() {
+        let bag: Multiset = "12 red, 13 green, 14 blue".parse().unwrap();
+        let draw: Multiset = "3 blue, 4 red".parse().unwrap();
+        assert!(draw.is_subset(&bag));
+        assert!(!bag.is_subset(&draw));
+        assert!(!"1 purple".parse::<Multiset>().unwrap().is_subset(&bag));
+
+        let other: Multiset = "6 blue, 2 green".parse().unwrap();
+        let union = draw.union(&other);
+        assert_eq!(union, "6 blue, 4 red, 2 green".parse().unwrap());
+        assert_eq!(union.product(["red", "green", "blue"]), 48);
+        assert_eq!(draw.product(["red", "green", "blue"]), 0);
+    }
+}
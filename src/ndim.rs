@@ -0,0 +1,404 @@
+use std::cmp::Ordering;
+use std::collections::hash_set::{self, HashSet};
+use std::ops::{Add, AddAssign, Deref, DerefMut, Mul, Sub, SubAssign};
+
+/// Position in `D` dimensional space, axis 0 is `x`, axis 1 is `y`, ...
+///
+/// [`Pos`](crate::Pos) is `PosN<2>`, its axes can also be reached as `pos.x` and `pos.y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PosN<const D: usize>(pub [isize; D]);
+
+pub type Pos3 = PosN<3>;
+
+/// Named axes of a `PosN<2>`.
+#[repr(C)]
+pub struct Xy {
+    pub x: isize,
+    pub y: isize,
+}
+
+/// Named dimensions of a `GridN<T, 2>`.
+#[repr(C)]
+pub struct Size {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Deref for PosN<2> {
+    type Target = Xy;
+
+    fn deref(&self) -> &Xy {
+        // SAFETY: `Xy` is `repr(C)` with two `isize` fields, the same layout as `[isize; 2]`
+        unsafe { &*(self.0.as_ptr() as *const Xy) }
+    }
+}
+
+impl DerefMut for PosN<2> {
+    fn deref_mut(&mut self) -> &mut Xy {
+        // SAFETY: see `deref`
+        unsafe { &mut *(self.0.as_mut_ptr() as *mut Xy) }
+    }
+}
+
+impl<const D: usize> From<[isize; D]> for PosN<D> {
+    fn from(f: [isize; D]) -> PosN<D> {
+        PosN(f)
+    }
+}
+
+/// Generalized reading order: the last axis first, so positions sort like
+/// their elements in a [`GridN`] and [`Pos`](crate::Pos) sorts top to bottom.
+impl<const D: usize> Ord for PosN<D> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<const D: usize> PartialOrd for PosN<D> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const D: usize> PosN<D> {
+    pub fn origin() -> PosN<D> {
+        PosN([0; D])
+    }
+
+    /// The vector of length one along `axis`.
+    pub fn unit(axis: usize) -> PosN<D> {
+        let mut p = PosN::origin();
+        p.0[axis] = 1;
+        p
+    }
+
+    pub fn manhattan(&self, other: &PosN<D>) -> usize {
+        self.0.iter().zip(other.0).map(|(a, b)| a.abs_diff(b)).sum()
+    }
+
+    pub fn chebyshev(&self, other: &PosN<D>) -> usize {
+        self.0
+            .iter()
+            .zip(other.0)
+            .map(|(a, b)| a.abs_diff(b))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The `2 * D` positions sharing a face with this one.
+    pub fn neighbors_orthogonal(&self) -> impl Iterator<Item = PosN<D>> + '_ {
+        (0..D).flat_map(move |axis| [*self - PosN::unit(axis), *self + PosN::unit(axis)])
+    }
+
+    /// The `3^D - 1` positions sharing at least a corner with this one.
+    pub fn neighbors_all(&self) -> impl Iterator<Item = PosN<D>> + '_ {
+        (0..3usize.pow(D as u32))
+            .map(move |mut i| {
+                let mut p = *self;
+                for c in p.0.iter_mut() {
+                    *c += (i % 3) as isize - 1;
+                    i /= 3;
+                }
+                p
+            })
+            .filter(move |p| p != self)
+    }
+}
+
+impl<const D: usize> Add<&PosN<D>> for &PosN<D> {
+    type Output = PosN<D>;
+
+    fn add(self, rhs: &PosN<D>) -> PosN<D> {
+        *self + *rhs
+    }
+}
+
+impl<const D: usize> Add for PosN<D> {
+    type Output = PosN<D>;
+
+    fn add(mut self, rhs: PosN<D>) -> PosN<D> {
+        self += rhs;
+        self
+    }
+}
+
+impl<const D: usize> AddAssign for PosN<D> {
+    fn add_assign(&mut self, rhs: PosN<D>) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a += b);
+    }
+}
+
+impl<const D: usize> Sub<&PosN<D>> for &PosN<D> {
+    type Output = PosN<D>;
+
+    fn sub(self, rhs: &PosN<D>) -> PosN<D> {
+        *self - *rhs
+    }
+}
+
+impl<const D: usize> Sub for PosN<D> {
+    type Output = PosN<D>;
+
+    fn sub(mut self, rhs: PosN<D>) -> PosN<D> {
+        self -= rhs;
+        self
+    }
+}
+
+impl<const D: usize> SubAssign for PosN<D> {
+    fn sub_assign(&mut self, rhs: PosN<D>) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a -= b);
+    }
+}
+
+impl<const D: usize> Mul<isize> for &PosN<D> {
+    type Output = PosN<D>;
+
+    fn mul(self, rhs: isize) -> PosN<D> {
+        *self * rhs
+    }
+}
+
+impl<const D: usize> Mul<isize> for PosN<D> {
+    type Output = PosN<D>;
+
+    fn mul(mut self, rhs: isize) -> PosN<D> {
+        self.0.iter_mut().for_each(|a| *a *= rhs);
+        self
+    }
+}
+
+/// Dense grid in `D` dimensions, stored with axis 0 varying fastest.
+///
+/// [`Map`](crate::Map) is `GridN<T, 2>`, its dimensions can also be reached
+/// as `map.width` and `map.height`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GridN<T, const D: usize> {
+    pub content: Vec<T>,
+    pub dims: [usize; D],
+}
+
+impl<T> Deref for GridN<T, 2> {
+    type Target = Size;
+
+    fn deref(&self) -> &Size {
+        // SAFETY: `Size` is `repr(C)` with two `usize` fields, the same layout as `[usize; 2]`
+        unsafe { &*(self.dims.as_ptr() as *const Size) }
+    }
+}
+
+impl<T> DerefMut for GridN<T, 2> {
+    fn deref_mut(&mut self) -> &mut Size {
+        // SAFETY: see `deref`
+        unsafe { &mut *(self.dims.as_mut_ptr() as *mut Size) }
+    }
+}
+
+impl<T: Clone, const D: usize> GridN<T, D> {
+    pub fn new(dims: [usize; D], fill: T) -> GridN<T, D> {
+        GridN {
+            content: vec![fill; dims.iter().product()],
+            dims,
+        }
+    }
+}
+
+impl<T, const D: usize> GridN<T, D> {
+    pub fn get_index(&self, pos: &PosN<D>) -> Option<usize> {
+        let mut index = 0;
+        for axis in (0..D).rev() {
+            let c = pos.0[axis];
+            if c < 0 || c >= self.dims[axis] as isize {
+                return None;
+            }
+            index = index * self.dims[axis] + c as usize;
+        }
+        Some(index)
+    }
+
+    pub fn get(&self, pos: &PosN<D>) -> Option<&T> {
+        self.content.get(self.get_index(pos)?)
+    }
+
+    pub fn get_mut(&mut self, pos: &PosN<D>) -> Option<&mut T> {
+        let index = self.get_index(pos)?;
+        self.content.get_mut(index)
+    }
+
+    /// Position of the element at `index` in `content`.
+    pub fn pos(&self, mut index: usize) -> PosN<D> {
+        let mut p = PosN::origin();
+        for axis in 0..D {
+            p.0[axis] = (index % self.dims[axis]) as isize;
+            index /= self.dims[axis];
+        }
+        p
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (PosN<D>, &T)> {
+        self.content
+            .iter()
+            .enumerate()
+            .map(|(i, el)| (self.pos(i), el))
+    }
+}
+
+/// Sparse set of occupied cells in `D` dimensions.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VoxelSet<const D: usize> {
+    cells: HashSet<PosN<D>>,
+}
+
+impl<const D: usize> VoxelSet<D> {
+    pub fn new() -> VoxelSet<D> {
+        VoxelSet {
+            cells: HashSet::new(),
+        }
+    }
+
+    /// Returns whether the cell was not occupied before.
+    pub fn insert(&mut self, pos: PosN<D>) -> bool {
+        self.cells.insert(pos)
+    }
+
+    /// Returns whether the cell was occupied before.
+    pub fn remove(&mut self, pos: &PosN<D>) -> bool {
+        self.cells.remove(pos)
+    }
+
+    pub fn contains(&self, pos: &PosN<D>) -> bool {
+        self.cells.contains(pos)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> hash_set::Iter<'_, PosN<D>> {
+        self.cells.iter()
+    }
+
+    /// Smallest and largest corner of the box containing all cells.
+    pub fn bounds(&self) -> Option<(PosN<D>, PosN<D>)> {
+        let mut cells = self.cells.iter();
+        let first = *cells.next()?;
+
+        Some(cells.fold((first, first), |(mut min, mut max), p| {
+            for axis in 0..D {
+                min.0[axis] = min.0[axis].min(p.0[axis]);
+                max.0[axis] = max.0[axis].max(p.0[axis]);
+            }
+            (min, max)
+        }))
+    }
+
+    /// Number of faces of occupied cells that touch an empty cell.
+    pub fn surface_area(&self) -> usize {
+        self.cells
+            .iter()
+            .flat_map(|p| p.neighbors_orthogonal())
+            .filter(|n| !self.contains(n))
+            .count()
+    }
+}
+
+impl<const D: usize> FromIterator<PosN<D>> for VoxelSet<D> {
+    fn from_iter<I: IntoIterator<Item = PosN<D>>>(iter: I) -> VoxelSet<D> {
+        VoxelSet {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Map, Pos};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_pos_n() {
+        let a = Pos3::from([1, 2, 3]);
+        let b = Pos3::from([-1, 0, 7]);
+
+        assert_eq!(a + b, [0, 2, 10].into());
+        assert_eq!(a - b, [2, 2, -4].into());
+        assert_eq!(a * -2, [-2, -4, -6].into());
+        assert_eq!(a.manhattan(&b), 8);
+        assert_eq!(a.chebyshev(&b), 4);
+
+        assert_eq!(a.neighbors_orthogonal().count(), 6);
+        assert_eq!(a.neighbors_all().count(), 26);
+        assert!(a.neighbors_all().all(|n| n.chebyshev(&a) == 1));
+
+        let mut p = Pos::from((4, -2));
+        assert_eq!(p, PosN([4, -2]));
+        assert_eq!((p.x, p.y), (4, -2));
+        p.y += 3;
+        assert_eq!(p.0, [4, 1]);
+        let p2: Vec<Pos> = p.neighbors_orthogonal().collect();
+        assert!(p.neighbors4().all(|n| p2.contains(&n)));
+    }
+
+    #[test]
+    fn test_pos_n_order() {
+        // reading order in 2-D
+        let mut pos: Vec<Pos> = [(2, 0), (0, 1), (1, 0), (-1, 1), (3, -1)]
+            .map(Pos::from)
+            .to_vec();
+        pos.sort();
+        let sorted = [(3, -1), (1, 0), (2, 0), (-1, 1), (0, 1)].map(Pos::from);
+        assert_eq!(pos, sorted);
+
+        // like the order of the elements in a grid
+        let grid = GridN::new([2, 3, 4], ());
+        let mut positions: Vec<Pos3> = grid.iter().map(|(p, _)| p).collect();
+        positions.reverse();
+        positions.sort();
+        assert_eq!(positions, grid.iter().map(|(p, _)| p).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_grid_n() {
+        let mut grid = GridN::new([2, 3, 4], 0);
+        assert_eq!(grid.content.len(), 24);
+
+        *grid.get_mut(&[1, 2, 3].into()).unwrap() = 5;
+        assert_eq!(grid.get(&[1, 2, 3].into()), Some(&5));
+        assert_eq!(grid.get(&[2, 0, 0].into()), None);
+        assert_eq!(grid.get(&[0, 0, -1].into()), None);
+        assert_eq!(
+            grid.pos(grid.get_index(&[1, 2, 3].into()).unwrap()),
+            [1, 2, 3].into()
+        );
+        assert_eq!(grid.iter().filter(|(_, &v)| v == 5).count(), 1);
+
+        let mut map: Map<char> = Map::from_str("abc\ndef\n").unwrap();
+        assert_eq!(map.get(&[0, 1].into()), Some(&'d'));
+        assert_eq!((map.width, map.height), (3, 2));
+        assert_eq!(map.dims, [3, 2]);
+        map.remove_col(0);
+        assert_eq!(map.dims, [2, 2]);
+        assert_eq!(map.to_string(), "bc\nef\n");
+    }
+
+    #[test]
+    fn test_voxel_set() {
+        let mut voxels: VoxelSet<3> = [[1, 1, 1], [2, 1, 1]].map(Pos3::from).into_iter().collect();
+        assert_eq!(voxels.surface_area(), 10);
+
+        assert!(voxels.insert([5, -1, 1].into()));
+        assert!(!voxels.insert([5, -1, 1].into()));
+        assert_eq!(voxels.len(), 3);
+        assert_eq!(voxels.surface_area(), 16);
+        assert_eq!(voxels.bounds(), Some(([1, -1, 1].into(), [5, 1, 1].into())));
+
+        assert!(voxels.remove(&[5, -1, 1].into()));
+        assert!(!voxels.contains(&[5, -1, 1].into()));
+        assert!(VoxelSet::<3>::new().bounds().is_none());
+    }
+}
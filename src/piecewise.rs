@@ -0,0 +1,287 @@
+use crate::IntervalSet;
+use std::error::Error;
+use std::ops::Range;
+
+/// Inputs in `src` are shifted to start at `dest`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piece {
+    pub src: Range<usize>,
+    pub dest: usize,
+}
+
+impl Piece {
+    fn len(&self) -> usize {
+        self.src.end - self.src.start
+    }
+
+    fn image(&self) -> Range<usize> {
+        self.dest..self.dest + self.len()
+    }
+}
+
+/// Map on `0..usize::MAX` made of shifted ranges, like the almanac maps of day 5.
+/// The pieces are sorted by source and cover the whole domain,
+/// gaps between the given pieces map to themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    pieces: Vec<Piece>,
+}
+
+impl Default for PiecewiseMap {
+    fn default() -> Self {
+        PiecewiseMap::identity()
+    }
+}
+
+impl PiecewiseMap {
+    pub fn identity() -> PiecewiseMap {
+        PiecewiseMap {
+            pieces: vec![Piece {
+                src: 0..usize::MAX,
+                dest: 0,
+            }],
+        }
+    }
+
+    /// Builds a map from pieces with disjoint sources.
+    pub fn from_pieces<I>(pieces: I) -> Result<PiecewiseMap, Box<dyn Error + 'static>>
+    where
+        I: IntoIterator<Item = Piece>,
+    {
+        let mut given: Vec<Piece> = pieces.into_iter().filter(|p| p.len() > 0).collect();
+        given.sort_unstable_by_key(|p| p.src.start);
+
+        let mut pieces = Vec::with_capacity(2 * given.len() + 1);
+        let mut covered = 0;
+        for p in given {
+            if p.src.start < covered {
+                return Err(format!("Source {:?} overlaps with another piece", p.src).into());
+            }
+            if p.dest.checked_add(p.len()).is_none() {
+                return Err(format!("Image of {:?} exceeds usize", p.src).into());
+            }
+            if p.src.start > covered {
+                pieces.push(Piece {
+                    src: covered..p.src.start,
+                    dest: covered,
+                });
+            }
+            covered = p.src.end;
+            pieces.push(p);
+        }
+        if covered < usize::MAX {
+            pieces.push(Piece {
+                src: covered..usize::MAX,
+                dest: covered,
+            });
+        }
+
+        Ok(PiecewiseMap::normalized(pieces))
+    }
+
+    /// Joins neighboring pieces that shift by the same amount.
+    fn normalized(pieces: Vec<Piece>) -> PiecewiseMap {
+        let mut joined: Vec<Piece> = Vec::with_capacity(pieces.len());
+        for p in pieces {
+            match joined.last_mut() {
+                Some(last) if last.src.end == p.src.start && last.image().end == p.dest => {
+                    last.src.end = p.src.end
+                }
+                _ => joined.push(p),
+            }
+        }
+        PiecewiseMap { pieces: joined }
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    pub fn apply(&self, x: usize) -> usize {
+        let i = self.pieces.partition_point(|p| p.src.end <= x);
+        match self.pieces.get(i) {
+            Some(p) => p.dest + (x - p.src.start),
+            None => x,
+        }
+    }
+
+    /// The map that applies `self` first and then `other`.
+    pub fn then(&self, other: &PiecewiseMap) -> PiecewiseMap {
+        let mut pieces = Vec::new();
+        for p in &self.pieces {
+            let image = p.image();
+            let first = other.pieces.partition_point(|q| q.src.end <= image.start);
+
+            for q in other.pieces[first..]
+                .iter()
+                .take_while(|q| q.src.start < image.end)
+            {
+                let start = image.start.max(q.src.start);
+                let end = image.end.min(q.src.end);
+                pieces.push(Piece {
+                    src: p.src.start + (start - image.start)..p.src.start + (end - image.start),
+                    dest: q.dest + (start - q.src.start),
+                });
+            }
+        }
+        pieces.sort_unstable_by_key(|p| p.src.start);
+        PiecewiseMap::normalized(pieces)
+    }
+
+    /// The inverse map, if every output has exactly one input.
+    pub fn invert(&self) -> Option<PiecewiseMap> {
+        let mut inverted: Vec<Piece> = self
+            .pieces
+            .iter()
+            .map(|p| Piece {
+                src: p.image(),
+                dest: p.src.start,
+            })
+            .collect();
+        inverted.sort_unstable_by_key(|p| p.src.start);
+
+        let mut covered = 0;
+        for p in &inverted {
+            if p.src.start != covered {
+                return None;
+            }
+            covered = p.src.end;
+        }
+        (covered == usize::MAX).then(|| PiecewiseMap::normalized(inverted))
+    }
+
+    /// All outputs of the inputs in `inputs`.
+    pub fn image(&self, inputs: &IntervalSet<usize>) -> IntervalSet<usize> {
+        let mut image = IntervalSet::new();
+        for p in &self.pieces {
+            for r in inputs.intersection(&p.src.clone().into()).iter() {
+                image.insert(p.dest + (r.start - p.src.start)..p.dest + (r.end - p.src.start));
+            }
+        }
+        image
+    }
+
+    /// All inputs that map into `outputs`.
+    pub fn preimage(&self, outputs: &IntervalSet<usize>) -> IntervalSet<usize> {
+        let mut preimage = IntervalSet::new();
+        for p in &self.pieces {
+            for r in outputs.intersection(&p.image().into()).iter() {
+                preimage.insert(p.src.start + (r.start - p.dest)..p.src.start + (r.end - p.dest));
+            }
+        }
+        preimage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed_to_soil() -> PiecewiseMap {
+        PiecewiseMap::from_pieces([
+            Piece {
+                src: 98..100,
+                dest: 50,
+            },
+            Piece {
+                src: 50..98,
+                dest: 52,
+            },
+        ])
+        .unwrap()
+    }
+
+    fn soil_to_fertilizer() -> PiecewiseMap {
+        PiecewiseMap::from_pieces([
+            Piece {
+                src: 15..52,
+                dest: 0,
+            },
+            Piece {
+                src: 52..54,
+                dest: 37,
+            },
+            Piece {
+                src: 0..15,
+                dest: 39,
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply() {
+        let map = seed_to_soil();
+        assert_eq!(map.pieces().len(), 3 + 1);
+        assert_eq!(map.apply(79), 81);
+        assert_eq!(map.apply(98), 50);
+        assert_eq!(map.apply(99), 51);
+        assert_eq!(map.apply(100), 100);
+        assert_eq!(map.apply(13), 13);
+
+        assert!(PiecewiseMap::from_pieces([
+            Piece {
+                src: 0..5,
+                dest: 10
+            },
+            Piece { src: 4..6, dest: 0 },
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_then() {
+        let composed = seed_to_soil().then(&soil_to_fertilizer());
+        for x in 0..200 {
+            assert_eq!(
+                composed.apply(x),
+                soil_to_fertilizer().apply(seed_to_soil().apply(x))
+            );
+        }
+        assert!(composed
+            .pieces()
+            .windows(2)
+            .all(|w| w[0].src.end == w[1].src.start));
+        assert_eq!(PiecewiseMap::identity().then(&composed), composed);
+        assert_eq!(composed.then(&PiecewiseMap::identity()), composed);
+    }
+
+    #[test]
+    fn test_invert() {
+        let map = seed_to_soil().then(&soil_to_fertilizer());
+        let inverse = map.invert().unwrap();
+        for x in 0..200 {
+            assert_eq!(inverse.apply(map.apply(x)), x);
+        }
+        assert_eq!(map.then(&inverse), PiecewiseMap::identity());
+
+        // 0 and 10 both map to 10
+        let not_injective = PiecewiseMap::from_pieces([Piece {
+            src: 0..1,
+            dest: 10,
+        }])
+        .unwrap();
+        assert!(not_injective.invert().is_none());
+    }
+
+    #[test]
+    fn test_image_preimage() {
+        let map = seed_to_soil();
+
+        let image = map.image(&(95..100).into());
+        assert_eq!(image.ranges(), &[50..52, 97..100]);
+
+        let preimage = map.preimage(&(50..53).into());
+        assert_eq!(preimage.ranges(), &[50..51, 98..100]);
+
+        let not_injective = PiecewiseMap::from_pieces([Piece {
+            src: 0..1,
+            dest: 10,
+        }])
+        .unwrap();
+        assert_eq!(
+            not_injective.preimage(&(10..11).into()).ranges(),
+            &[0..1, 10..11]
+        );
+    }
+}
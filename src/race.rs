@@ -0,0 +1,234 @@
+use crate::math::isqrt;
+use std::ops::Range;
+
+/// Whether holding the button for `hold` ms of a `time` ms race beats the `record`,
+/// the boat then travels `hold * (time - hold)`.
+pub fn wins(time: u64, record: u64, hold: u64) -> bool {
+    hold <= time && hold as u128 * (time - hold) as u128 > record as u128
+}
+
+/// All hold times that beat the `record`, a tie is no win.
+///
+/// These are the `h` with `h^2 - time * h + record < 0`, between the roots
+/// `(time ± sqrt(time^2 - 4 * record)) / 2` of the quadratic.
+pub fn winning_holds(time: u64, record: u64) -> Range<u64> {
+    let (t, d) = (time as u128, record as u128);
+    let Some(discriminant) = (t * t).checked_sub(4 * d) else {
+        return 0..0;
+    };
+
+    // the root rounded down, then moved to the first winning hold
+    let mut low = ((t - isqrt(discriminant)) / 2) as u64;
+    while low > 0 && wins(time, record, low - 1) {
+        low -= 1;
+    }
+    while low <= time / 2 && !wins(time, record, low) {
+        low += 1;
+    }
+
+    // the distance is symmetric around time / 2
+    if low > time / 2 {
+        0..0
+    } else {
+        low..time - low + 1
+    }
+}
+
+/// How far a boat travels depending on how long the button is held.
+/// The distance has to increase up to the best hold time and decrease afterwards.
+pub trait RaceModel {
+    /// Distance traveled in a `time` ms race when holding the button for `hold` ms.
+    fn distance(&self, time: u64, hold: u64) -> u128;
+
+    /// The shortest hold time that travels farthest.
+    fn optimal_hold(&self, time: u64) -> u64 {
+        partition_point(0, time, |h| {
+            self.distance(time, h + 1) <= self.distance(time, h)
+        })
+    }
+
+    /// All hold times that beat the `record`, a tie is no win.
+    fn winning_holds(&self, time: u64, record: u64) -> Range<u64> {
+        let record = record as u128;
+        let peak = self.optimal_hold(time);
+        if self.distance(time, peak) <= record {
+            return 0..0;
+        }
+        let low = partition_point(0, peak, |h| self.distance(time, h) > record);
+        let high = partition_point(peak, time.saturating_add(1), |h| {
+            self.distance(time, h) <= record
+        });
+        low..high
+    }
+
+    /// Number of hold times that beat the `record`.
+    fn count_wins(&self, time: u64, record: u64) -> u64 {
+        let holds = self.winning_holds(time, record);
+        holds.end - holds.start
+    }
+}
+
+/// The first `x` in `low..high` for which `pred` holds, or `high` if there is none.
+/// `pred` has to be false up to some point and true afterwards.
+fn partition_point<P: Fn(u64) -> bool>(mut low: u64, mut high: u64, pred: P) -> u64 {
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    low
+}
+
+/// The puzzle's boat, every ms of holding adds 1 mm/ms of speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Linear;
+
+impl RaceModel for Linear {
+    fn distance(&self, time: u64, hold: u64) -> u128 {
+        hold as u128 * time.saturating_sub(hold) as u128
+    }
+
+    fn optimal_hold(&self, time: u64) -> u64 {
+        time / 2
+    }
+
+    fn winning_holds(&self, time: u64, record: u64) -> Range<u64> {
+        winning_holds(time, record)
+    }
+}
+
+/// The speed grows with the square of the hold time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accelerating;
+
+impl RaceModel for Accelerating {
+    fn distance(&self, time: u64, hold: u64) -> u128 {
+        (hold as u128)
+            .saturating_mul(hold as u128)
+            .saturating_mul(time.saturating_sub(hold) as u128)
+    }
+}
+
+/// Like [`Linear`], but holding longer than `max_speed` ms adds no more speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capped {
+    pub max_speed: u64,
+}
+
+impl RaceModel for Capped {
+    fn distance(&self, time: u64, hold: u64) -> u128 {
+        hold.min(self.max_speed) as u128 * time.saturating_sub(hold) as u128
+    }
+}
+
+/// Like [`Linear`], but every ms of charging costs `cost` additional ms of the race.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChargeCost {
+    pub cost: u64,
+}
+
+impl RaceModel for ChargeCost {
+    fn distance(&self, time: u64, hold: u64) -> u128 {
+        let spent = (hold as u128).saturating_mul(self.cost as u128 + 1);
+        hold as u128 * (time as u128).saturating_sub(spent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lcg;
+
+    #[test]
+    fn test_winning_holds() {
+        assert_eq!(winning_holds(7, 9), 2..6);
+        assert_eq!(winning_holds(15, 40), 4..12);
+        // the roots 10 and 20 are ties
+        assert_eq!(winning_holds(30, 200), 11..20);
+        assert_eq!(Linear.count_wins(71530, 940200), 71503);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        // a perfect square discriminant of zero only ties at 3
+        assert_eq!(Linear.count_wins(6, 9), 0);
+        assert_eq!(winning_holds(6, 8), 3..4);
+        // odd times have two best holds
+        assert_eq!(winning_holds(7, 11), 3..5);
+        assert_eq!(Linear.count_wins(7, 12), 0);
+        assert_eq!(Linear.count_wins(0, 0), 0);
+        assert_eq!(winning_holds(5, 0), 1..5);
+
+        // no overflow close to the limits
+        let t = u64::MAX;
+        let holds = winning_holds(t, u64::MAX);
+        assert!(!wins(t, u64::MAX, holds.start - 1) && wins(t, u64::MAX, holds.start));
+        assert_eq!(holds.end, t - holds.start + 1);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let mut rng = Lcg(2023);
+        for _ in 0..2000 {
+            let t = rng.next(300);
+            // mostly records close to the best distance t^2 / 4
+            let d = match rng.next(3) {
+                0 => t * t / 4 - rng.next(t / 2 + 1).min(t * t / 4),
+                1 => t * t / 4 + rng.next(3),
+                _ => rng.next(t * t / 4 + 1),
+            };
+            let wins = (1..t).filter(|&h| h * (t - h) > d).count() as u64;
+            assert_eq!(Linear.count_wins(t, d), wins, "time {t} record {d}");
+        }
+    }
+
+    /// Winning holds by trying all of them.
+    fn brute_force<M: RaceModel>(model: &M, time: u64, record: u64) -> Vec<u64> {
+        (0..=time)
+            .filter(|&h| model.distance(time, h) > record as u128)
+            .collect()
+    }
+
+    fn check<M: RaceModel>(model: &M) {
+        for time in 0..40 {
+            let best = (0..=time).map(|h| model.distance(time, h)).max().unwrap();
+            assert_eq!(
+                model.distance(time, model.optimal_hold(time)),
+                best,
+                "time {time}"
+            );
+
+            for record in (0..best as u64 + 2).step_by(1 + best as usize / 50) {
+                let holds = model.winning_holds(time, record);
+                assert_eq!(
+                    holds.clone().collect::<Vec<_>>(),
+                    brute_force(model, time, record),
+                    "time {time} record {record}"
+                );
+                assert_eq!(model.count_wins(time, record), holds.end - holds.start);
+            }
+        }
+    }
+
+    #[test]
+    fn test_models() {
+        check(&Linear);
+        check(&Accelerating);
+        check(&Capped { max_speed: 5 });
+        check(&Capped { max_speed: 0 });
+        check(&ChargeCost { cost: 0 });
+        check(&ChargeCost { cost: 2 });
+
+        assert_eq!(Linear.count_wins(30, 200), 9);
+        assert_eq!(Accelerating.optimal_hold(30), 20);
+        assert_eq!(Capped { max_speed: 5 }.optimal_hold(30), 5);
+        assert_eq!(ChargeCost { cost: 2 }.optimal_hold(30), 5);
+        assert_eq!(
+            ChargeCost { cost: 0 }.winning_holds(30, 200),
+            Linear.winning_holds(30, 200)
+        );
+    }
+}
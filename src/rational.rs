@@ -0,0 +1,272 @@
+use crate::bigint::BigInt;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// Exact fraction, always reduced with a positive denominator.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: BigInt,
+    denom: BigInt,
+}
+
+impl Rational {
+    /// # Panics
+    /// If `denom` is zero.
+    pub fn new<N: Into<BigInt>, D: Into<BigInt>>(numer: N, denom: D) -> Rational {
+        let (numer, denom) = (numer.into(), denom.into());
+        assert!(!denom.is_zero(), "Denominator of {numer}/{denom} is zero");
+        let mut g = numer.gcd(&denom);
+        if denom.is_negative() {
+            g = -g;
+        }
+        Rational {
+            numer: &numer / &g,
+            denom: &denom / &g,
+        }
+    }
+
+    pub fn zero() -> Rational {
+        Rational::from(BigInt::zero())
+    }
+
+    pub fn one() -> Rational {
+        Rational::from(BigInt::one())
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        &self.numer
+    }
+
+    pub fn denom(&self) -> &BigInt {
+        &self.denom
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == BigInt::one()
+    }
+
+    pub fn to_integer(&self) -> Option<BigInt> {
+        self.is_integer().then(|| self.numer.clone())
+    }
+
+    /// The largest integer not above.
+    pub fn floor(&self) -> BigInt {
+        self.numer.div_floor(&self.denom)
+    }
+
+    /// The smallest integer not below.
+    pub fn ceil(&self) -> BigInt {
+        -(-&self.numer).div_floor(&self.denom)
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(n: BigInt) -> Rational {
+        Rational {
+            numer: n,
+            denom: BigInt::one(),
+        }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Rational {
+        Rational::from(BigInt::from(n))
+    }
+}
+
+impl From<i32> for Rational {
+    fn from(n: i32) -> Rational {
+        Rational::from(BigInt::from(n))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: &Rational) -> Rational {
+        Rational::new(
+            &self.numer * &rhs.denom + &rhs.numer * &self.denom,
+            &self.denom * &rhs.denom,
+        )
+    }
+}
+
+impl Sub<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: &Rational) -> Rational {
+        self + &-rhs
+    }
+}
+
+impl Mul<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: &Rational) -> Rational {
+        Rational::new(&self.numer * &rhs.numer, &self.denom * &rhs.denom)
+    }
+}
+
+/// # Panics
+/// If `rhs` is zero.
+impl Div<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn div(self, rhs: &Rational) -> Rational {
+        Rational::new(&self.numer * &rhs.denom, &self.denom * &rhs.numer)
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numer: -&self.numer,
+            denom: self.denom.clone(),
+        }
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        -&self
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Rational) -> Rational {
+        &self + &rhs
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Rational) -> Rational {
+        &self - &rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Rational) -> Rational {
+        &self * &rhs
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, rhs: Rational) -> Rational {
+        &self / &rhs
+    }
+}
+
+/// Parses `a/b` or a plain integer `a`.
+impl FromStr for Rational {
+    type Err = Box<dyn Error + 'static>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((numer, denom)) => {
+                let denom: BigInt = denom.trim().parse()?;
+                if denom.is_zero() {
+                    return Err(format!("Denominator of {s:?} is zero").into());
+                }
+                Ok(Rational::new(numer.trim().parse::<BigInt>()?, denom))
+            }
+            None => Ok(Rational::from(s.trim().parse::<BigInt>()?)),
+        }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let half = Rational::new(2, 4);
+        assert_eq!(half, Rational::new(-1, -2));
+        assert_eq!(
+            (half.numer(), half.denom()),
+            (&BigInt::from(1), &BigInt::from(2))
+        );
+
+        let third = Rational::new(1, 3);
+        assert_eq!(&half + &third, Rational::new(5, 6));
+        assert_eq!(&third - &half, Rational::new(-1, 6));
+        assert_eq!(&half * &third, Rational::new(1, 6));
+        assert_eq!(&third / &half, Rational::new(2, 3));
+        assert_eq!((&half + &half).to_integer(), Some(BigInt::one()));
+        assert_eq!(third.to_integer(), None);
+        assert!(third < half && -half.clone() < -third.clone());
+
+        assert_eq!(Rational::new(3, -6).to_string(), "-1/2");
+        assert_eq!(Rational::from(-7).to_string(), "-7");
+        assert_eq!(Rational::new(0, -5), Rational::zero());
+    }
+
+    #[test]
+    fn test_floor_ceil() {
+        for (n, d, floor, ceil) in [(7, 2, 3, 4), (-7, 2, -4, -3), (6, 3, 2, 2), (-1, 3, -1, 0)] {
+            let r = Rational::new(n, d);
+            assert_eq!(r.floor(), BigInt::from(floor), "{r}");
+            assert_eq!(r.ceil(), BigInt::from(ceil), "{r}");
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("6/-4".parse::<Rational>().unwrap(), Rational::new(-3, 2));
+        assert_eq!(" 12 ".parse::<Rational>().unwrap(), Rational::from(12));
+        assert!("1/0".parse::<Rational>().is_err());
+        assert!("1/x".parse::<Rational>().is_err());
+
+        // beyond 64 bit, (10^30 + 1) / 10^30 squared
+        let r: Rational = "1000000000000000000000000000001/1000000000000000000000000000000"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            (&r * &r).to_string(),
+            "1000000000000000000000000000002000000000000000000000000000001/\
+             1000000000000000000000000000000000000000000000000000000000000"
+        );
+    }
+}
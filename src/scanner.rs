@@ -0,0 +1,258 @@
+use std::collections::{HashMap, VecDeque};
+
+/// A pattern found in a text, `start..end` are byte offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'a, V> {
+    pub start: usize,
+    pub end: usize,
+    pub value: &'a V,
+}
+
+/// Aho-Corasick automaton over the bytes of a set of patterns.
+#[derive(Debug, Clone)]
+struct Trie {
+    /// Transitions of every node, node 0 is the root.
+    next: Vec<HashMap<u8, usize>>,
+    /// Node of the longest proper suffix that is also in the trie.
+    fail: Vec<usize>,
+    /// Patterns ending at each node, including those of its suffixes.
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Trie {
+    fn new<'a, I: IntoIterator<Item = &'a [u8]>>(patterns: I) -> Trie {
+        let mut trie = Trie {
+            next: vec![HashMap::new()],
+            fail: vec![0],
+            outputs: vec![Vec::new()],
+        };
+        for (id, pattern) in patterns.into_iter().enumerate() {
+            let mut node = 0;
+            for &b in pattern {
+                node = match trie.next[node].get(&b) {
+                    Some(&n) => n,
+                    None => {
+                        trie.next.push(HashMap::new());
+                        trie.fail.push(0);
+                        trie.outputs.push(Vec::new());
+                        let n = trie.next.len() - 1;
+                        trie.next[node].insert(b, n);
+                        n
+                    }
+                };
+            }
+            trie.outputs[node].push(id);
+        }
+
+        // suffix links in breadth first order, so the links of shorter prefixes are known
+        let mut queue: VecDeque<usize> = trie.next[0].values().copied().collect();
+        while let Some(node) = queue.pop_front() {
+            let children: Vec<(u8, usize)> =
+                trie.next[node].iter().map(|(&b, &n)| (b, n)).collect();
+            for (b, child) in children {
+                let fail = if node == 0 {
+                    0
+                } else {
+                    trie.step(trie.fail[node], b)
+                };
+                trie.fail[child] = fail;
+                let inherited = trie.outputs[fail].clone();
+                trie.outputs[child].extend(inherited);
+                queue.push_back(child);
+            }
+        }
+        trie
+    }
+
+    fn step(&self, mut node: usize, b: u8) -> usize {
+        loop {
+            if let Some(&n) = self.next[node].get(&b) {
+                return n;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = self.fail[node];
+        }
+    }
+
+    /// The match starting first as `(start, pattern)`, preferring longer patterns.
+    fn leftmost<I: Iterator<Item = u8>>(&self, bytes: I, lens: &[usize]) -> Option<(usize, usize)> {
+        let max_len = lens.iter().copied().max().unwrap_or(0);
+        let mut best: Option<(usize, usize)> = None;
+        let mut node = 0;
+        for (i, b) in bytes.enumerate() {
+            node = self.step(node, b);
+            for &id in &self.outputs[node] {
+                let start = i + 1 - lens[id];
+                if best.is_none_or(|(s, other)| (start, lens[other]) < (s, lens[id])) {
+                    best = Some((start, id));
+                }
+            }
+            // later matches start after the best one
+            if best.is_some_and(|(start, _)| i + 1 >= start + max_len) {
+                break;
+            }
+        }
+        best
+    }
+}
+
+/// Finds all occurrences of a set of patterns in a single pass, overlapping ones included.
+/// Each pattern carries a value, like the number a word stands for.
+#[derive(Debug, Clone)]
+pub struct Scanner<V> {
+    patterns: Vec<(String, V)>,
+    lens: Vec<usize>,
+    forward: Trie,
+    /// The reversed patterns, to scan from the end.
+    backward: Trie,
+}
+
+impl<V> Scanner<V> {
+    /// # Panics
+    /// If a pattern is empty.
+    pub fn new<S: Into<String>, I: IntoIterator<Item = (S, V)>>(patterns: I) -> Scanner<V> {
+        let patterns: Vec<(String, V)> = patterns.into_iter().map(|(p, v)| (p.into(), v)).collect();
+        assert!(patterns.iter().all(|(p, _)| !p.is_empty()), "Empty pattern");
+        let reversed: Vec<Vec<u8>> = patterns
+            .iter()
+            .map(|(p, _)| p.bytes().rev().collect())
+            .collect();
+        Scanner {
+            lens: patterns.iter().map(|(p, _)| p.len()).collect(),
+            forward: Trie::new(patterns.iter().map(|(p, _)| p.as_bytes())),
+            backward: Trie::new(reversed.iter().map(Vec::as_slice)),
+            patterns,
+        }
+    }
+
+    pub fn patterns(&self) -> impl Iterator<Item = (&str, &V)> + '_ {
+        self.patterns.iter().map(|(p, v)| (p.as_str(), v))
+    }
+
+    fn found(&self, start: usize, id: usize) -> Match<'_, V> {
+        Match {
+            start,
+            end: start + self.lens[id],
+            value: &self.patterns[id].1,
+        }
+    }
+
+    /// All matches ordered by their end, longer ones first.
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match<'a, V>> + 'a {
+        text.bytes()
+            .enumerate()
+            .scan(0, move |node, (i, b)| {
+                *node = self.forward.step(*node, b);
+                Some((i + 1, *node))
+            })
+            .flat_map(move |(end, node)| {
+                let mut ids = self.forward.outputs[node].clone();
+                ids.sort_unstable_by_key(|&id| std::cmp::Reverse(self.lens[id]));
+                ids.into_iter()
+                    .map(move |id| self.found(end - self.lens[id], id))
+            })
+    }
+
+    /// The match starting first, the longest one if several start there.
+    pub fn first(&self, text: &str) -> Option<Match<'_, V>> {
+        self.forward
+            .leftmost(text.bytes(), &self.lens)
+            .map(|(start, id)| self.found(start, id))
+    }
+
+    /// The match ending last, the longest one if several end there.
+    /// Only scans as far from the end as needed.
+    pub fn last(&self, text: &str) -> Option<Match<'_, V>> {
+        self.backward
+            .leftmost(text.bytes().rev(), &self.lens)
+            .map(|(from_end, id)| self.found(text.len() - from_end - self.lens[id], id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lcg;
+
+    fn words() -> Scanner<usize> {
+        Scanner::new([
+            ("one", 1),
+            ("two", 2),
+            ("eight", 8),
+            ("ten", 10),
+            ("seventeen", 17),
+            ("teen", 0),
+        ])
+    }
+
+    #[test]
+    fn test_find_iter() {
+        let scanner = words();
+        let found: Vec<(usize, usize, usize)> = scanner
+            .find_iter("xeightwone seventeen")
+            .map(|m| (m.start, m.end, *m.value))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(1, 6, 8), (5, 8, 2), (7, 10, 1), (11, 20, 17), (16, 20, 0)]
+        );
+        assert_eq!(scanner.find_iter("").count(), 0);
+        assert_eq!(scanner.find_iter("oneone").count(), 2);
+    }
+
+    #[test]
+    fn test_first_last() {
+        let scanner = words();
+        let first = scanner.first("xeightwone").unwrap();
+        assert_eq!((first.start, first.end, *first.value), (1, 6, 8));
+        let last = scanner.last("xeightwone").unwrap();
+        assert_eq!((last.start, last.end, *last.value), (7, 10, 1));
+
+        // the longest of the matches ending last
+        assert_eq!(*scanner.last("twoseventeen").unwrap().value, 17);
+        assert_eq!(*scanner.first("tenteen").unwrap().value, 10);
+        assert_eq!(*scanner.last("tenteen").unwrap().value, 0);
+        // a short pattern is found first but the long one starts earlier
+        let scanner = Scanner::new([("abcd", 'l'), ("bc", 's')]);
+        assert_eq!(*scanner.first("xabcd").unwrap().value, 'l');
+        assert_eq!(*scanner.last("abcdx").unwrap().value, 'l');
+        assert!(scanner.first("abd").is_none() && scanner.last("abd").is_none());
+    }
+
+    #[test]
+    fn test_against_naive() {
+        let scanner = Scanner::new([("ab", 0), ("bab", 1), ("b", 2), ("aaa", 3), ("abba", 4)]);
+        let mut rng = Lcg(1);
+        for _ in 0..200 {
+            let len = rng.next(12) as usize;
+            let text: String = (0..len).map(|_| ['a', 'b'][rng.next(2) as usize]).collect();
+            let mut naive: Vec<(usize, usize, usize)> = scanner
+                .patterns()
+                .flat_map(|(p, &v)| {
+                    let text = &text;
+                    (0..=text.len().saturating_sub(p.len()))
+                        .filter(move |&s| text[s..].starts_with(p))
+                        .map(move |s| (s, s + p.len(), v))
+                })
+                .collect();
+            naive.sort_by_key(|&(s, e, _)| (e, std::cmp::Reverse(e - s)));
+            let found: Vec<(usize, usize, usize)> = scanner
+                .find_iter(&text)
+                .map(|m| (m.start, m.end, *m.value))
+                .collect();
+            assert_eq!(found, naive, "{text}");
+
+            let first = naive
+                .iter()
+                .min_by_key(|&&(s, e, _)| (s, std::cmp::Reverse(e)));
+            let last = naive
+                .iter()
+                .max_by_key(|&&(s, e, _)| (e, std::cmp::Reverse(s)));
+            let as_tuple = |m: Match<usize>| (m.start, m.end, *m.value);
+            assert_eq!(scanner.first(&text).map(as_tuple).as_ref(), first, "{text}");
+            assert_eq!(scanner.last(&text).map(as_tuple).as_ref(), last, "{text}");
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::error::Error;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Largest number a [`NumberSet`] parses, it takes one bit per number up to its largest.
+pub const MAX_NUMBER: usize = 1 << 16;
+
+/// Set of small numbers, bit `n % 64` of word `n / 64` is set if `n` is in it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberSet {
+    words: Vec<u64>,
+}
+
+impl NumberSet {
+    /// Whether `n` was new.
+    pub fn insert(&mut self, n: usize) -> bool {
+        if self.words.len() <= n / 64 {
+            self.words.resize(n / 64 + 1, 0);
+        }
+        let bit = 1 << (n % 64);
+        let new = self.words[n / 64] & bit == 0;
+        self.words[n / 64] |= bit;
+        new
+    }
+
+    pub fn contains(&self, n: usize) -> bool {
+        self.words
+            .get(n / 64)
+            .is_some_and(|w| w & 1 << (n % 64) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Number of elements in both sets.
+    pub fn common(&self, other: &NumberSet) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+}
+
+/// Parses whitespace separated numbers up to [`MAX_NUMBER`], rejecting others and duplicates.
+impl FromStr for NumberSet {
+    type Err = Box<dyn Error + 'static>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = NumberSet::default();
+        for token in s.split_whitespace() {
+            let n: usize = token
+                .parse()
+                .map_err(|e| format!("Invalid number {token:?}: {e}"))?;
+            if n > MAX_NUMBER {
+                return Err(format!("Number {n} is above {MAX_NUMBER}").into());
+            }
+            if !set.insert(n) {
+                return Err(format!("Duplicate number {n} in {s:?}").into());
+            }
+        }
+        Ok(set)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Scratchcard {
+    pub id: usize,
+    pub winning: NumberSet,
+    pub numbers: NumberSet,
+}
+
+impl Scratchcard {
+    /// How many of the numbers are winning numbers.
+    pub fn matches(&self) -> usize {
+        self.winning.common(&self.numbers)
+    }
+}
+
+impl FromStr for Scratchcard {
+    type Err = Box<dyn Error + 'static>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (head, body) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Missing ':' in {s:?}"))?;
+        let id = head
+            .strip_prefix("Card")
+            .ok_or_else(|| format!("Not a card: {s:?}"))?
+            .trim()
+            .parse()?;
+        let (winning, numbers) = body
+            .split_once('|')
+            .ok_or_else(|| format!("Missing '|' in {s:?}"))?;
+
+        Ok(Scratchcard {
+            id,
+            winning: winning.parse()?,
+            numbers: numbers.parse()?,
+        })
+    }
+}
+
+/// How many points a card with some matches is worth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payout {
+    /// One point for the first match, doubled for every further one.
+    Doubling,
+    /// One point per match.
+    Linear,
+    /// `table[m]` points for `m` matches, the last entry for all beyond.
+    Table(Vec<usize>),
+}
+
+impl Payout {
+    /// Fails if the points don't fit into a `usize`.
+    pub fn points(&self, matches: usize) -> Result<usize, Box<dyn Error + 'static>> {
+        Ok(match self {
+            Payout::Doubling => match matches {
+                0 => 0,
+                m => u32::try_from(m - 1)
+                    .ok()
+                    .and_then(|shift| 1usize.checked_shl(shift))
+                    .ok_or_else(|| format!("{m} matches are worth too many points"))?,
+            },
+            Payout::Linear => matches,
+            Payout::Table(table) => table.get(matches).or(table.last()).copied().unwrap_or(0),
+        })
+    }
+}
+
+/// Every copy of a card wins one copy of each of the next cards, as many as it has matches.
+/// Cards beyond the end of the table are not won.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cascade {
+    /// Cards won by each card, as indices.
+    wins: Vec<Range<usize>>,
+    /// Copies of each card in the end, originals included.
+    copies: Vec<usize>,
+    /// Number of cards one copy of each card ends up adding, itself included.
+    sizes: Vec<usize>,
+}
+
+/// Card `card` and, below it, the trees of the cards it won.
+/// The tree grows exponentially with the cards, so it is only walked on demand.
+#[derive(Debug, Clone, Copy)]
+pub struct CascadeTree<'a> {
+    cascade: &'a Cascade,
+    pub card: usize,
+}
+
+impl<'a> CascadeTree<'a> {
+    pub fn children(&self) -> impl Iterator<Item = CascadeTree<'a>> + 'a {
+        let cascade = self.cascade;
+        cascade.wins[self.card]
+            .clone()
+            .map(move |card| CascadeTree { cascade, card })
+    }
+
+    /// Number of cards in the tree.
+    pub fn size(&self) -> usize {
+        self.cascade.sizes[self.card]
+    }
+}
+
+impl Cascade {
+    /// Fails if the number of copies doesn't fit into a `usize`.
+    pub fn simulate(cards: &[Scratchcard]) -> Result<Cascade, Box<dyn Error + 'static>> {
+        let wins: Vec<Range<usize>> = cards
+            .iter()
+            .enumerate()
+            .map(|(i, card)| (i + 1).min(cards.len())..(i + 1 + card.matches()).min(cards.len()))
+            .collect();
+        let overflow = |card: usize| format!("Too many copies of card {}", cards[card].id);
+
+        // cards only win later cards, so their copies are final when reached
+        let mut copies = vec![1usize; cards.len()];
+        for (i, won) in wins.iter().enumerate() {
+            for j in won.clone() {
+                copies[j] = copies[j]
+                    .checked_add(copies[i])
+                    .ok_or_else(|| overflow(j))?;
+            }
+        }
+        // and the trees of later cards are known first from the back
+        let mut sizes = vec![1usize; cards.len()];
+        for (i, won) in wins.iter().enumerate().rev() {
+            for j in won.clone() {
+                sizes[i] = sizes[i].checked_add(sizes[j]).ok_or_else(|| overflow(i))?;
+            }
+        }
+        Ok(Cascade {
+            wins,
+            copies,
+            sizes,
+        })
+    }
+
+    pub fn copies(&self) -> &[usize] {
+        &self.copies
+    }
+
+    /// Fails if the total doesn't fit into a `usize`.
+    pub fn total(&self) -> Result<usize, Box<dyn Error + 'static>> {
+        self.copies
+            .iter()
+            .try_fold(0usize, |acc, &n| acc.checked_add(n))
+            .ok_or_else(|| "Too many cards in total".into())
+    }
+
+    /// Everything one copy of card `card` wins.
+    pub fn tree(&self, card: usize) -> CascadeTree<'_> {
+        CascadeTree {
+            cascade: self,
+            card,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_set() {
+        let a: NumberSet = "1 64 200".parse().unwrap();
+        let b: NumberSet = "200 2 64".parse().unwrap();
+        assert_eq!(a.len(), 3);
+        assert_eq!(a.common(&b), 2);
+        assert_eq!(b.common(&a), 2);
+        assert_eq!(a.common(&NumberSet::default()), 0);
+        assert!(NumberSet::default().is_empty() && !a.is_empty());
+        assert!(a.contains(200) && !a.contains(199) && !a.contains(100_000));
+
+        assert!("1 2 x".parse::<NumberSet>().is_err());
+        assert!("1 2 1".parse::<NumberSet>().is_err());
+        assert!("-1".parse::<NumberSet>().is_err());
+        // would take gigabytes of bits
+        assert!("99999999999".parse::<NumberSet>().is_err());
+        assert!(format!("{MAX_NUMBER}").parse::<NumberSet>().is_ok());
+    }
+
+    #[test]
+    fn test_parse() {
+        let card: Scratchcard = "Card  12: 41 48 | 83 41  6".parse().unwrap();
+        assert_eq!(card.id, 12);
+        assert_eq!(card.matches(), 1);
+
+        assert!("Card 1: 41 4x | 83".parse::<Scratchcard>().is_err());
+        assert!("Card 1: 41 41 | 83".parse::<Scratchcard>().is_err());
+        assert!("Card 1: 41 48 83".parse::<Scratchcard>().is_err());
+        assert!("Cart 1: 41 | 83".parse::<Scratchcard>().is_err());
+        assert!("Card x: 41 | 83".parse::<Scratchcard>().is_err());
+    }
+
+    #[test]
+    fn test_payouts() {
+        let points = |payout: Payout| {
+            (0..5)
+                .map(|m| payout.points(m).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(points(Payout::Doubling), vec![0, 1, 2, 4, 8]);
+        assert_eq!(points(Payout::Linear), vec![0, 1, 2, 3, 4]);
+        assert_eq!(
+            points(Payout::Table(vec![0, 5, 10])),
+            vec![0, 5, 10, 10, 10]
+        );
+        assert_eq!(points(Payout::Table(vec![])), vec![0; 5]);
+        assert_eq!(Payout::Doubling.points(64).unwrap(), 1 << 63);
+        assert!(Payout::Doubling.points(65).is_err());
+    }
+
+    /// The cards of a tree in depth first order.
+    fn walk(tree: CascadeTree) -> Vec<usize> {
+        let mut cards = vec![tree.card];
+        for child in tree.children() {
+            cards.extend(walk(child));
+        }
+        cards
+    }
+
+    #[test]
+    fn test_cascade() {
+        let cards: Vec<Scratchcard> = ["Card 1: 1 2 | 1 2", "Card 2: 3 | 3", "Card 3: 4 | 5"]
+            .iter()
+            .map(|c| c.parse().unwrap())
+            .collect();
+        let cascade = Cascade::simulate(&cards).unwrap();
+        assert_eq!(cascade.copies(), &[1, 2, 4]);
+        assert_eq!(cascade.total().unwrap(), 7);
+        assert_eq!(walk(cascade.tree(0)), vec![0, 1, 2, 2]);
+        assert_eq!(cascade.tree(0).size(), 4);
+        assert_eq!(cascade.tree(2).children().count(), 0);
+
+        // wins beyond the last card are lost
+        let cascade = Cascade::simulate(&cards[1..]).unwrap();
+        assert_eq!(cascade.copies(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_cascade_overflow() {
+        // every card wins all later ones, so card n has 2^n copies
+        let card = |id: usize| -> Scratchcard {
+            let numbers: Vec<String> = (1..=80).map(|n| n.to_string()).collect();
+            let numbers = numbers.join(" ");
+            format!("Card {id}: {numbers} | {numbers}").parse().unwrap()
+        };
+        let mut cards: Vec<Scratchcard> = (1..=65).map(card).collect();
+        let cascade = Cascade::simulate(&cards[..64]).unwrap();
+        assert_eq!(cascade.total().unwrap(), usize::MAX);
+        assert_eq!(cascade.tree(0).size(), 1 << 63);
+        assert!(Cascade::simulate(&cards).is_err());
+
+        // one more card that wins nothing
+        cards[64] = "Card 0: 1 | 2".parse().unwrap();
+        cards.rotate_right(1);
+        assert!(Cascade::simulate(&cards).unwrap().total().is_err());
+    }
+}
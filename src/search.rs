@@ -0,0 +1,381 @@
+use crate::{Map, Pos};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Distances from the start node and the tree of predecessors
+/// of every node a search reached.
+#[derive(Debug, Clone)]
+pub struct SearchResult<N> {
+    pub dist: HashMap<N, usize>,
+    pub pred: HashMap<N, N>,
+}
+
+impl<N: Hash + Eq + Clone> SearchResult<N> {
+    fn new(start: N) -> Self {
+        SearchResult {
+            dist: HashMap::from([(start, 0)]),
+            pred: HashMap::new(),
+        }
+    }
+
+    /// Path from the start node to `to`, both ends included.
+    /// Returns `None` if `to` was never reached.
+    pub fn path(&self, to: &N) -> Option<Vec<N>> {
+        if !self.dist.contains_key(to) {
+            return None;
+        }
+
+        let mut path = vec![to.clone()];
+        while let Some(prev) = self.pred.get(path.last().unwrap()) {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth first search, every edge has length one.
+pub fn bfs<N, F, I>(start: N, mut neighbors: F) -> SearchResult<N>
+where
+    N: Hash + Eq + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut result = SearchResult::new(start.clone());
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        let d = result.dist[&node];
+        for next in neighbors(&node) {
+            if result.dist.contains_key(&next) {
+                continue;
+            }
+            result.dist.insert(next.clone(), d + 1);
+            result.pred.insert(next.clone(), node.clone());
+            queue.push_back(next);
+        }
+    }
+
+    result
+}
+
+/// Depth first search, the distances are the depths in the search tree.
+/// Nodes are visited when popped, so every node hangs below the node
+/// that reached it first in depth first order, like a recursive search would.
+pub fn dfs<N, F, I>(start: N, mut neighbors: F) -> SearchResult<N>
+where
+    N: Hash + Eq + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut result = SearchResult {
+        dist: HashMap::new(),
+        pred: HashMap::new(),
+    };
+    let mut stack = vec![(start, None, 0)];
+
+    while let Some((node, parent, d)) = stack.pop() {
+        if result.dist.contains_key(&node) {
+            continue;
+        }
+        result.dist.insert(node.clone(), d);
+        if let Some(parent) = parent {
+            result.pred.insert(node.clone(), parent);
+        }
+        // reversed, so the first neighbor is explored first
+        let next: Vec<N> = neighbors(&node)
+            .into_iter()
+            .filter(|next| !result.dist.contains_key(next))
+            .collect();
+        for next in next.into_iter().rev() {
+            stack.push((next, Some(node.clone()), d + 1));
+        }
+    }
+
+    result
+}
+
+/// Entry of the priority queue, ordered such that
+/// the `BinaryHeap` pops the lowest `priority` first.
+struct Visit<N> {
+    priority: usize,
+    cost: usize,
+    node: N,
+}
+
+impl<N> PartialEq for Visit<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N> Eq for Visit<N> {}
+
+impl<N> PartialOrd for Visit<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Visit<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Shortest paths from `start` to every reachable node.
+/// `neighbors` yields the next nodes together with the cost of the edge.
+pub fn dijkstra<N, F, I>(start: N, mut neighbors: F) -> SearchResult<N>
+where
+    N: Hash + Eq + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut result = SearchResult::new(start.clone());
+    let mut heap = BinaryHeap::from([Visit {
+        priority: 0,
+        cost: 0,
+        node: start,
+    }]);
+
+    while let Some(Visit { cost, node, .. }) = heap.pop() {
+        if cost > result.dist[&node] {
+            continue;
+        }
+
+        for (next, edge) in neighbors(&node) {
+            let next_cost = cost + edge;
+            if result.dist.get(&next).is_some_and(|&d| d <= next_cost) {
+                continue;
+            }
+            result.dist.insert(next.clone(), next_cost);
+            result.pred.insert(next.clone(), node.clone());
+            heap.push(Visit {
+                priority: next_cost,
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+
+    result
+}
+
+/// Shortest path from `start` to the first node for which `is_goal` holds.
+/// `heuristic` must never overestimate the remaining cost, otherwise the
+/// returned path is not guaranteed to be the shortest.
+pub fn astar<N, F, I, H, G>(
+    start: N,
+    mut neighbors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Hash + Eq + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, usize)>,
+    H: FnMut(&N) -> usize,
+    G: FnMut(&N) -> bool,
+{
+    let mut result = SearchResult::new(start.clone());
+    let mut heap = BinaryHeap::from([Visit {
+        priority: heuristic(&start),
+        cost: 0,
+        node: start,
+    }]);
+
+    while let Some(Visit { cost, node, .. }) = heap.pop() {
+        if cost > result.dist[&node] {
+            continue;
+        }
+        if is_goal(&node) {
+            return Some((cost, result.path(&node).unwrap()));
+        }
+
+        for (next, edge) in neighbors(&node) {
+            let next_cost = cost + edge;
+            if result.dist.get(&next).is_some_and(|&d| d <= next_cost) {
+                continue;
+            }
+            result.dist.insert(next.clone(), next_cost);
+            result.pred.insert(next.clone(), node.clone());
+            heap.push(Visit {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+
+    None
+}
+
+/// Neighbor function for [`bfs`] and [`dfs`] that moves between orthogonally
+/// adjacent cells of `map`, as long as `can_move(from, to)` allows it.
+/// Positions outside of `map` have no neighbors.
+pub fn map_neighbors<'a, T, F>(map: &'a Map<T>, can_move: F) -> impl Fn(&Pos) -> Vec<Pos> + 'a
+where
+    F: Fn(&T, &T) -> bool + 'a,
+{
+    move |pos| {
+        let Some(from) = map.get(pos) else {
+            return vec![];
+        };
+        pos.neighbors4()
+            .filter(|next| map.get(next).is_some_and(|to| can_move(from, to)))
+            .collect()
+    }
+}
+
+/// Neighbor function for [`dijkstra`] and [`astar`] over orthogonally
+/// adjacent cells of `map`. `cost(from, to)` returns `None` for
+/// impassable edges. Positions outside of `map` have no neighbors.
+pub fn map_weighted_neighbors<'a, T, F>(
+    map: &'a Map<T>,
+    cost: F,
+) -> impl Fn(&Pos) -> Vec<(Pos, usize)> + 'a
+where
+    F: Fn(&T, &T) -> Option<usize> + 'a,
+{
+    move |pos| {
+        let Some(from) = map.get(pos) else {
+            return vec![];
+        };
+        pos.neighbors4()
+            .filter_map(|next| Some((next, cost(from, map.get(&next)?)?)))
+            .collect()
+    }
+}
+
+/// Neighbor function for nodes that have exactly two (left and right) successors,
+/// like the network of day 8.
+pub fn pair_neighbors<'a, N>(adjacency: &'a HashMap<N, (N, N)>) -> impl Fn(&N) -> Vec<N> + 'a
+where
+    N: Hash + Eq + Clone,
+{
+    move |node| match adjacency.get(node) {
+        Some((left, right)) => vec![left.clone(), right.clone()],
+        None => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const MAZE: &str = "S.#.
+.##.
+...E
+";
+
+    const WEIGHTS: &str = "1163
+1381
+2136
+";
+
+    #[test]
+    fn test_bfs_map() {
+        let map: Map<char> = Map::from_str(MAZE).unwrap();
+        let result = bfs((0, 0).into(), map_neighbors(&map, |_, &to| to != '#'));
+
+        assert_eq!(result.dist[&(3, 2).into()], 5);
+        assert_eq!(result.dist[&(3, 0).into()], 7);
+        assert!(!result.dist.contains_key(&(2, 0).into()));
+        assert_eq!(
+            result.path(&(3, 2).into()).unwrap(),
+            vec![
+                (0, 0).into(),
+                (0, 1).into(),
+                (0, 2).into(),
+                (1, 2).into(),
+                (2, 2).into(),
+                (3, 2).into()
+            ]
+        );
+        assert_eq!(result.path(&(2, 0).into()), None);
+    }
+
+    #[test]
+    fn test_dfs_reachability() {
+        let map: Map<char> = Map::from_str(MAZE).unwrap();
+        let bfs = bfs((0, 0).into(), map_neighbors(&map, |_, &to| to != '#'));
+        let dfs = dfs((0, 0).into(), map_neighbors(&map, |_, &to| to != '#'));
+
+        let mut reached_bfs: Vec<_> = bfs.dist.keys().map(|p| (p.x, p.y)).collect();
+        let mut reached_dfs: Vec<_> = dfs.dist.keys().map(|p| (p.x, p.y)).collect();
+        reached_bfs.sort();
+        reached_dfs.sort();
+        assert_eq!(reached_bfs, reached_dfs);
+        assert_eq!(reached_dfs.len(), 9);
+
+        let path = dfs.path(&(3, 0).into()).unwrap();
+        assert_eq!(path.len(), dfs.dist[&(3, 0).into()] + 1);
+    }
+
+    #[test]
+    fn test_dfs_order() {
+        // 2 is reached below 1 before 0 gets to it
+        let graph = HashMap::from([(0, vec![1, 2]), (1, vec![2]), (2, vec![])]);
+        let result = dfs(0, |n| graph[n].clone());
+        assert_eq!(result.dist[&2], 2);
+        assert_eq!(result.path(&2).unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_outside_map() {
+        let map: Map<char> = Map::from_str(MAZE).unwrap();
+        let outside = Pos::from((4, 0));
+        assert!(map_neighbors(&map, |_, _| true)(&outside).is_empty());
+        assert!(map_weighted_neighbors(&map, |_, _| Some(1))(&outside).is_empty());
+        assert_eq!(bfs(outside, map_neighbors(&map, |_, _| true)).dist.len(), 1);
+    }
+
+    #[test]
+    fn test_dijkstra_astar() {
+        let map: Map<u32> = Map {
+            content: WEIGHTS.chars().filter_map(|c| c.to_digit(10)).collect(),
+            dims: [4, 3],
+        };
+        let goal = Pos::from((3, 2));
+
+        let result = dijkstra(
+            (0, 0).into(),
+            map_weighted_neighbors(&map, |_, &to| Some(to as usize)),
+        );
+        assert_eq!(result.dist[&goal], 13);
+        assert_eq!(result.path(&goal).unwrap().len(), 6);
+
+        let (cost, path) = astar(
+            Pos::from((0, 0)),
+            map_weighted_neighbors(&map, |_, &to| Some(to as usize)),
+            |p| p.manhattan(&goal),
+            |p| *p == goal,
+        )
+        .unwrap();
+        assert_eq!(cost, 13);
+        assert_eq!(path, result.path(&goal).unwrap());
+
+        assert!(astar(
+            Pos::from((0, 0)),
+            map_weighted_neighbors(&map, |_, &to| (to < 3).then_some(1)),
+            |_| 0,
+            |p| *p == goal,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_pair_neighbors() {
+        let network = HashMap::from([
+            ("AAA", ("BBB", "BBB")),
+            ("BBB", ("AAA", "ZZZ")),
+            ("ZZZ", ("ZZZ", "ZZZ")),
+        ]);
+
+        let result = bfs("AAA", pair_neighbors(&network));
+        assert_eq!(result.dist["ZZZ"], 2);
+        assert_eq!(result.path(&"ZZZ").unwrap(), vec!["AAA", "BBB", "ZZZ"]);
+    }
+}
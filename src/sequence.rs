@@ -0,0 +1,249 @@
+use crate::bigint::BigInt;
+use crate::rational::Rational;
+use std::error::Error;
+use std::fmt;
+
+/// Polynomial with exact rational coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    /// Lowest degree first, without trailing zeros.
+    coeffs: Vec<Rational>,
+}
+
+impl Polynomial {
+    pub fn new(mut coeffs: Vec<Rational>) -> Polynomial {
+        while coeffs.last().is_some_and(Rational::is_zero) {
+            coeffs.pop();
+        }
+        Polynomial { coeffs }
+    }
+
+    /// The polynomial of lowest degree through all `(x, y)` points, by Lagrange interpolation.
+    pub fn interpolate(points: &[(i64, i64)]) -> Result<Polynomial, Box<dyn Error + 'static>> {
+        let points: Vec<(Rational, Rational)> =
+            points.iter().map(|&(x, y)| (x.into(), y.into())).collect();
+        Polynomial::interpolate_exact(&points)
+    }
+
+    /// Like [`Polynomial::interpolate`], for points with rational coordinates.
+    pub fn interpolate_exact(
+        points: &[(Rational, Rational)],
+    ) -> Result<Polynomial, Box<dyn Error + 'static>> {
+        let mut coeffs = vec![Rational::zero(); points.len()];
+        for (i, (xi, yi)) in points.iter().enumerate() {
+            // basis polynomial that is 1 at xi and 0 at every other x
+            let mut basis = vec![Rational::one()];
+            let mut scale = yi.clone();
+            for (j, (xj, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                if xi == xj {
+                    return Err(format!("Two points with x = {xi}").into());
+                }
+                // multiply by (x - xj)
+                basis.insert(0, Rational::zero());
+                for k in 0..basis.len() - 1 {
+                    basis[k] = &basis[k] - &(&basis[k + 1] * xj);
+                }
+                scale = scale / (xi - xj);
+            }
+
+            for (c, b) in coeffs.iter_mut().zip(basis) {
+                *c = &*c + &(b * scale.clone());
+            }
+        }
+        Ok(Polynomial::new(coeffs))
+    }
+
+    /// Lowest degree first.
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coeffs
+    }
+
+    /// `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn eval(&self, x: &Rational) -> Rational {
+        // Horner's method
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(Rational::zero(), |acc, c| &(&acc * x) + c)
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if self.coeffs.is_empty() {
+            return write!(f, "0");
+        }
+        let terms: Vec<String> = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| !c.is_zero())
+            .map(|(k, c)| match k {
+                0 => format!("{c}"),
+                1 => format!("{c}x"),
+                _ => format!("{c}x^{k}"),
+            })
+            .collect();
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+/// Integer sequence given by a polynomial in its index,
+/// stored as the first entries of its difference table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    /// `diffs[k]` is the `k`-th difference at index 0.
+    diffs: Vec<BigInt>,
+}
+
+impl Sequence {
+    /// The sequence whose first values are `samples`.
+    /// If the differences don't reach all zeros within the samples,
+    /// it is the polynomial of degree `samples.len() - 1` through all of them.
+    pub fn from_samples(samples: &[i64]) -> Result<Sequence, Box<dyn Error + 'static>> {
+        if samples.is_empty() {
+            return Err("No samples".into());
+        }
+        let mut row: Vec<BigInt> = samples.iter().map(|&s| BigInt::from(s)).collect();
+        let mut diffs = Vec::new();
+        while !row.iter().all(BigInt::is_zero) {
+            diffs.push(row[0].clone());
+            row = row.windows(2).map(|w| &w[1] - &w[0]).collect();
+        }
+        Ok(Sequence { diffs })
+    }
+
+    /// `None` for the all zero sequence.
+    pub fn degree(&self) -> Option<usize> {
+        self.diffs.len().checked_sub(1)
+    }
+
+    /// The exact value at index `n`, which may be negative or beyond the samples.
+    pub fn exact_at(&self, n: &BigInt) -> BigInt {
+        // Newton's forward formula, the sum of diffs[k] * binomial(n, k)
+        let mut binomial = BigInt::one();
+        let mut value = BigInt::zero();
+        for (k, d) in self.diffs.iter().enumerate() {
+            value += &(d * &binomial);
+            // binomial(n, k) * (n - k) is divisible by k + 1
+            binomial = binomial * (n - BigInt::from(k)) / BigInt::from(k + 1);
+        }
+        value
+    }
+
+    /// The value at index `n`, `None` if it does not fit into an `i64`.
+    pub fn at(&self, n: i64) -> Option<i64> {
+        i64::try_from(&self.exact_at(&n.into())).ok()
+    }
+
+    pub fn polynomial(&self) -> Polynomial {
+        let points: Vec<(Rational, Rational)> = (0..self.diffs.len())
+            .map(BigInt::from)
+            .map(|n| (n.clone().into(), self.exact_at(&n).into()))
+            .collect();
+        Polynomial::interpolate_exact(&points).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        // triangle numbers, n (n + 1) / 2
+        let p = Polynomial::interpolate(&[(0, 0), (1, 1), (2, 3)]).unwrap();
+        assert_eq!(
+            p.coefficients(),
+            &[Rational::zero(), Rational::new(1, 2), Rational::new(1, 2)]
+        );
+        assert_eq!(p.degree(), Some(2));
+        assert_eq!(p.to_string(), "1/2x^2 + 1/2x");
+        assert_eq!(p.eval(&100.into()), 5050.into());
+        assert_eq!(p.eval(&Rational::new(1, 2)), Rational::new(3, 8));
+
+        // points in any order and spacing
+        let p = Polynomial::interpolate(&[(5, 3), (-2, 3), (1, -9)]).unwrap();
+        assert_eq!(p.eval(&5.into()), 3.into());
+        assert_eq!(p.eval(&(-2).into()), 3.into());
+        assert_eq!(p.eval(&1.into()), (-9).into());
+        assert_eq!(p.degree(), Some(2));
+
+        assert_eq!(
+            Polynomial::interpolate(&[(1, 4), (2, 4)])
+                .unwrap()
+                .to_string(),
+            "4"
+        );
+        assert_eq!(Polynomial::interpolate(&[]).unwrap().degree(), None);
+        assert!(Polynomial::interpolate(&[(1, 4), (1, 5)]).is_err());
+    }
+
+    #[test]
+    fn test_sequence() {
+        let seq = Sequence::from_samples(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(seq.degree(), Some(3));
+        assert_eq!(seq.at(6), Some(68));
+        assert_eq!(seq.at(-1), Some(5));
+        assert_eq!(seq.at(0), Some(10));
+        for n in -20..20 {
+            assert_eq!(
+                Rational::from(seq.at(n).unwrap()),
+                seq.polynomial().eval(&n.into())
+            );
+        }
+
+        let squares = Sequence::from_samples(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(squares.at(3_000_000_000), Some(9_000_000_000_000_000_000));
+        assert_eq!(squares.at(-3_000_000_000), Some(9_000_000_000_000_000_000));
+        assert_eq!(squares.at(4_000_000_000), None);
+        let huge: BigInt = "100000000000000000000".parse().unwrap();
+        assert_eq!(squares.exact_at(&huge), huge.pow(2));
+        assert_eq!(squares.exact_at(&-&huge), huge.pow(2));
+
+        assert_eq!(Sequence::from_samples(&[0, 0]).unwrap().degree(), None);
+        assert_eq!(Sequence::from_samples(&[0, 0]).unwrap().at(-5), Some(0));
+    }
+
+    #[test]
+    fn test_few_samples() {
+        // powers of two never reach zero, so they are taken as a quartic
+        let seq = Sequence::from_samples(&[1, 2, 4, 8, 16]).unwrap();
+        assert_eq!(seq.degree(), Some(4));
+        assert_eq!(seq.at(5), Some(31));
+        let p = Polynomial::interpolate(&[(0, 1), (1, 2), (2, 4), (3, 8), (4, 16)]).unwrap();
+        assert_eq!(seq.polynomial(), p);
+
+        // three samples of a quadratic give the quadratic
+        assert_eq!(Sequence::from_samples(&[0, 1, 4]).unwrap().at(5), Some(25));
+        assert_eq!(Sequence::from_samples(&[7]).unwrap().at(-3), Some(7));
+        assert!(Sequence::from_samples(&[]).is_err());
+
+        // a degree 20 polynomial from just 21 values
+        let samples: Vec<i64> = (0..21i128)
+            .map(|n| (n.pow(20) / 1_000_000_000) as i64)
+            .collect();
+        let seq = Sequence::from_samples(&samples).unwrap();
+        assert_eq!(seq.degree(), Some(20));
+        for (n, &s) in samples.iter().enumerate() {
+            assert_eq!(seq.at(n as i64), Some(s));
+        }
+    }
+
+    #[test]
+    fn test_interpolate_far_apart() {
+        // x - x' doesn't fit into an i64
+        let p = Polynomial::interpolate(&[(i64::MIN, 0), (i64::MAX, 1)]).unwrap();
+        assert_eq!(p.eval(&i64::MIN.into()), Rational::zero());
+        assert_eq!(p.eval(&i64::MAX.into()), Rational::one());
+        assert_eq!(p.degree(), Some(1));
+    }
+}
@@ -0,0 +1,311 @@
+use crate::{Map, Pos};
+use std::collections::hash_map::{self, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// Grid without fixed size that only stores occupied cells.
+/// Parses and prints like a [`Map`], where characters that do not
+/// convert into a `T` are empty cells, which are printed as `.`.
+/// A parsed grid keeps its full size when printed, even with empty rows or columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Pos, T>,
+    /// Upper left and lower right corner (inclusive) of all cells.
+    bounds: Option<(Pos, Pos)>,
+    /// Corners (inclusive) of the text or [`Map`] the grid came from.
+    frame: Option<(Pos, Pos)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+            frame: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, pos: &Pos) -> Option<&T> {
+        self.cells.get(pos)
+    }
+
+    pub fn get_mut(&mut self, pos: &Pos) -> Option<&mut T> {
+        self.cells.get_mut(pos)
+    }
+
+    pub fn contains(&self, pos: &Pos) -> bool {
+        self.cells.contains_key(pos)
+    }
+
+    /// Returns the previous element at `pos`.
+    pub fn insert(&mut self, pos: Pos, el: T) -> Option<T> {
+        self.bounds = Some(match self.bounds.take() {
+            Some((min, max)) => (
+                (min.x.min(pos.x), min.y.min(pos.y)).into(),
+                (max.x.max(pos.x), max.y.max(pos.y)).into(),
+            ),
+            None => (pos, pos),
+        });
+        self.cells.insert(pos, el)
+    }
+
+    pub fn remove(&mut self, pos: &Pos) -> Option<T> {
+        let el = self.cells.remove(pos)?;
+
+        if let Some((min, max)) = &self.bounds {
+            if pos.x == min.x || pos.y == min.y || pos.x == max.x || pos.y == max.y {
+                self.update_bounds();
+            }
+        }
+        Some(el)
+    }
+
+    /// Keeps only the cells for which `keep` returns true.
+    pub fn retain<F: FnMut(&Pos, &mut T) -> bool>(&mut self, keep: F) {
+        self.cells.retain(keep);
+        self.update_bounds();
+    }
+
+    fn update_bounds(&mut self) {
+        self.bounds = self.cells.keys().fold(None, |bounds, pos| {
+            Some(match bounds {
+                Some((min, max)) => (
+                    (pos.x.min(min.x), pos.y.min(min.y)).into(),
+                    (pos.x.max(max.x), pos.y.max(max.y)).into(),
+                ),
+                None => (*pos, *pos),
+            })
+        });
+    }
+
+    /// Upper left and lower right corner of the smallest rectangle containing all cells,
+    /// both inclusive.
+    pub fn bounds(&self) -> Option<&(Pos, Pos)> {
+        self.bounds.as_ref()
+    }
+
+    /// Corners of the area that is printed, both inclusive: the bounds
+    /// together with the text or [`Map`] the grid came from.
+    pub fn extent(&self) -> Option<(Pos, Pos)> {
+        match (&self.bounds, &self.frame) {
+            (Some((min, max)), Some((fmin, fmax))) => Some((
+                (min.x.min(fmin.x), min.y.min(fmin.y)).into(),
+                (max.x.max(fmax.x), max.y.max(fmax.y)).into(),
+            )),
+            (Some(area), None) | (None, Some(area)) => Some(*area),
+            (None, None) => None,
+        }
+    }
+
+    /// A frame from `(0, 0)` of the given size, `None` if it is empty.
+    fn frame_of(width: usize, height: usize) -> Option<(Pos, Pos)> {
+        (width > 0 && height > 0).then(|| {
+            (
+                (0, 0).into(),
+                (width as isize - 1, height as isize - 1).into(),
+            )
+        })
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Pos, T> {
+        self.cells.iter()
+    }
+
+    pub fn positions(&self) -> hash_map::Keys<'_, Pos, T> {
+        self.cells.keys()
+    }
+
+    /// The occupied cells among the 4 orthogonal neighbors of `pos`.
+    pub fn neighbors4<'a>(&'a self, pos: &'a Pos) -> impl Iterator<Item = (Pos, &'a T)> + 'a {
+        pos.neighbors4()
+            .filter_map(|n| self.cells.get(&n).map(|el| (n, el)))
+    }
+
+    /// The occupied cells among the 8 surrounding neighbors of `pos`.
+    pub fn neighbors8<'a>(&'a self, pos: &'a Pos) -> impl Iterator<Item = (Pos, &'a T)> + 'a {
+        pos.neighbors8()
+            .filter_map(|n| self.cells.get(&n).map(|el| (n, el)))
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Dense copy of the [`SparseGrid::extent`], empty cells are set to `fill`.
+    /// Its upper left corner becomes `(0, 0)`.
+    pub fn to_map(&self, fill: T) -> Option<Map<T>> {
+        let (min, max) = self.extent()?;
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+
+        let mut map = Map {
+            content: vec![fill; width * height],
+            dims: [width, height],
+        };
+        for (pos, el) in &self.cells {
+            *map.get_mut(&(pos - &min)).unwrap() = el.clone();
+        }
+
+        Some(map)
+    }
+}
+
+impl<T> From<Map<T>> for SparseGrid<T> {
+    fn from(map: Map<T>) -> SparseGrid<T> {
+        let width = map.width;
+        let mut grid = SparseGrid::new();
+        grid.frame = SparseGrid::<T>::frame_of(map.width, map.height);
+        for (i, el) in map.content.into_iter().enumerate() {
+            grid.insert(((i % width) as isize, (i / width) as isize).into(), el);
+        }
+        grid
+    }
+}
+
+impl<T: TryFrom<char>> FromStr for SparseGrid<T> {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut grid = SparseGrid::new();
+        let mut width = 0;
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                width = width.max(x + 1);
+                if let Ok(el) = T::try_from(c) {
+                    grid.insert((x as isize, y as isize).into(), el);
+                }
+            }
+        }
+        grid.frame = SparseGrid::<T>::frame_of(width, s.lines().count());
+        Ok(grid)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let Some((min, max)) = self.extent() else {
+            return Ok(());
+        };
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                match self.get(&(x, y).into()) {
+                    Some(el) => write!(f, "{}", el)?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Rock;
+
+    impl TryFrom<char> for Rock {
+        type Error = ();
+
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            match c {
+                '#' => Ok(Rock),
+                _ => Err(()),
+            }
+        }
+    }
+
+    impl fmt::Display for Rock {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "#")
+        }
+    }
+
+    const TEST: &str = "#..
+..#
+.#.
+";
+
+    #[test]
+    fn test_parse_display() {
+        let grid: SparseGrid<Rock> = TEST.parse().unwrap();
+        assert_eq!(grid.len(), 3);
+        assert!(grid.contains(&(2, 1).into()));
+        assert_eq!(grid.bounds(), Some(&((0, 0).into(), (2, 2).into())));
+        assert_eq!(grid.to_string(), TEST);
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let mut grid: SparseGrid<Rock> = TEST.parse().unwrap();
+
+        grid.insert((-1_000_000, 5).into(), Rock);
+        assert_eq!(
+            grid.bounds(),
+            Some(&((-1_000_000, 0).into(), (2, 5).into()))
+        );
+
+        assert_eq!(grid.remove(&(-1_000_000, 5).into()), Some(Rock));
+        assert_eq!(grid.remove(&(0, 0).into()), Some(Rock));
+        assert_eq!(grid.remove(&(0, 0).into()), None);
+        assert_eq!(grid.bounds(), Some(&((1, 1).into(), (2, 2).into())));
+        // still printed at the parsed size
+        assert_eq!(grid.to_string(), "...\n..#\n.#.\n");
+
+        let mut grid: SparseGrid<Rock> = SparseGrid::new();
+        grid.insert((3, -2).into(), Rock);
+        grid.insert((4, -1).into(), Rock);
+        assert_eq!(grid.to_string(), "#.\n.#\n");
+        grid.insert((2, 1).into(), Rock);
+        assert_eq!(grid.to_string(), ".#.\n..#\n...\n#..\n");
+    }
+
+    #[test]
+    fn test_blank_edges() {
+        for text in ["...\n.#.\n", "....\n....\n", "..#\n"] {
+            let grid: SparseGrid<Rock> = text.parse().unwrap();
+            assert_eq!(grid.to_string(), text);
+            let map: Map<char> = text.parse().unwrap();
+            assert_eq!(SparseGrid::from(map).to_map('.').unwrap().to_string(), text);
+        }
+        assert_eq!("".parse::<SparseGrid<Rock>>().unwrap().to_string(), "");
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid: SparseGrid<Rock> = TEST.parse().unwrap();
+        let center = Pos::from((1, 1));
+
+        assert_eq!(grid.neighbors4(&center).count(), 2);
+        assert_eq!(grid.neighbors8(&center).count(), 3);
+    }
+
+    #[test]
+    fn test_map_conversion() {
+        let map: Map<char> = TEST.parse().unwrap();
+        let mut grid: SparseGrid<char> = map.into();
+        assert_eq!(grid.len(), 9);
+
+        grid.retain(|_, &mut c| c == '#');
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.to_string(), TEST);
+        assert_eq!(grid.to_map(' ').unwrap().to_string(), "#  \n  #\n # \n");
+
+        assert!(SparseGrid::<char>::new().to_map(' ').is_none());
+    }
+}